@@ -0,0 +1,61 @@
+/* C interface to the letter boxed solver, see src/ffi.rs
+ *
+ *   LbSolver *solver = lb_solver_new("rme wca ots ihn", "word-list.txt");
+ *   LbStatus status;
+ *   LbSolutions *solutions = lb_solver_solve(solver, NULL, &status);
+ *   if (status != LB_OK)
+ *       return status;
+ *   for (size_t i = 0; i < solutions->count; i++) {
+ *       LbSolution *solution = &solutions->solutions[i];
+ *       for (size_t j = 0; j < solution->word_count; j++)
+ *           printf("%s ", solution->words[j]);
+ *       printf("\n");
+ *   }
+ *   lb_solution_free(solutions);
+ *   lb_solver_free(solver);
+ */
+#ifndef LBSOLVER_H
+#define LBSOLVER_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct LbSolver LbSolver;
+
+/* how lb_solver_solve went */
+typedef enum {
+    LB_OK = 0,               /* solved, with no solutions if the board has none */
+    LB_INVALID_ARGUMENT = 1, /* a NULL solver, or ignore words that aren't UTF-8 */
+    LB_TIMEOUT = 2,          /* ran out of time or nodes before finding any */
+    LB_ERROR = 3,            /* anything else the solver couldn't do */
+    LB_PANIC = 4,            /* the solver failed, and shouldn't be used again */
+} LbStatus;
+
+typedef struct {
+    char **words;
+    size_t word_count;
+    size_t letter_count;
+} LbSolution;
+
+typedef struct {
+    LbSolution *solutions;
+    size_t count;
+} LbSolutions;
+
+/* NULL if the board or the word list is no good */
+LbSolver *lb_solver_new(const char *sides, const char *dictionary_path);
+void lb_solver_set_max_words(LbSolver *solver, size_t max_words);
+/* ignore_words is space separated and can be NULL, and so can status.
+ * NULL unless the status is LB_OK */
+LbSolutions *lb_solver_solve(LbSolver *solver, const char *ignore_words, LbStatus *status);
+void lb_solution_free(LbSolutions *solutions);
+void lb_solver_free(LbSolver *solver);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
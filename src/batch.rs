@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use lbsolver::{json, Definitions, Dictionary, Frequencies, LbError, Puzzle, Solution};
+
+use crate::{
+    configure, print_solution, print_table_header, print_table_rows, solutions_json,
+    tidy_solutions, Args, Format,
+};
+
+// solve every board in the source, one per line: the groups of letters,
+// then any words to ignore for that board, e.g. "abc def ghi jkl word".
+// blank lines and lines starting with # are skipped
+//
+// the word list is only read once for the whole batch, and each board
+// gets its own record: a block of text, a line of JSON, or a row of csv
+// or tsv for each of its solutions under a single header. the frequency
+// list and definitions are loaded once too, when they're given.
+// --jobs solves that many boards at once
+pub fn run<W: Write, R: BufRead>(
+    out: &mut W,
+    args: &Args,
+    dictionary: &Dictionary,
+    frequencies: Option<&Frequencies>,
+    definitions: Option<&Definitions>,
+    source: R,
+) -> io::Result<()> {
+    if let Some(separator) = args.format.separator() {
+        print_table_header(out, separator)?;
+    }
+    if args.jobs > 1 {
+        let boards = source
+            .lines()
+            .filter_map(|line| line.map(|line| parse_line(args, &line)).transpose())
+            .collect::<io::Result<Vec<Board>>>()?;
+        return run_jobs(out, args, dictionary, frequencies, definitions, &boards);
+    }
+    for line in source.lines() {
+        if let Some(board) = parse_line(args, &line?) {
+            let found_solutions = solve(args, dictionary, frequencies, &board);
+            print_board(out, args, &board, found_solutions, frequencies, definitions)?;
+        }
+    }
+    Ok(())
+}
+
+// a board from the file, with the words to ignore for it
+struct Board {
+    groups: Vec<String>,
+    ignore_words: Vec<String>,
+}
+
+fn parse_line(args: &Args, line: &str) -> Option<Board> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let mut groups: Vec<String> = line.split_whitespace().map(str::to_lowercase).collect();
+    let mut ignore_words = args.ignore_words.clone();
+    ignore_words.extend(groups.split_off(args.sides.min(groups.len())));
+    Some(Board {
+        groups,
+        ignore_words,
+    })
+}
+
+// with --jobs the boards are shared out between that many threads, each
+// taking the next board not yet started when it's done with one. the
+// boards are printed as soon as the ones before them have been, so the
+// output is in the same order as the file
+fn run_jobs<W: Write>(
+    out: &mut W,
+    args: &Args,
+    dictionary: &Dictionary,
+    frequencies: Option<&Frequencies>,
+    definitions: Option<&Definitions>,
+    boards: &[Board],
+) -> io::Result<()> {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..args.jobs.min(boards.len()) {
+            let (next, sender) = (&next, sender.clone());
+            scope.spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(board) = boards.get(i) else {
+                    break;
+                };
+                let found_solutions = solve(args, dictionary, frequencies, board);
+                if sender.send((i, found_solutions)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut finished = BTreeMap::new();
+        let mut printed = 0;
+        for (i, found_solutions) in receiver {
+            finished.insert(i, found_solutions);
+            while let Some(found_solutions) = finished.remove(&printed) {
+                print_board(
+                    out,
+                    args,
+                    &boards[printed],
+                    found_solutions,
+                    frequencies,
+                    definitions,
+                )?;
+                printed += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn solve(
+    args: &Args,
+    dictionary: &Dictionary,
+    frequencies: Option<&Frequencies>,
+    board: &Board,
+) -> Result<(Puzzle, Vec<Solution>), LbError> {
+    let puzzle = Puzzle::with_group_size(&board.groups, args.group_size)?;
+    let mut solver = dictionary.solver_for(&puzzle);
+    configure(&mut solver, args);
+    let mut solutions = if args.pairs {
+        solver.pairs(&board.ignore_words)
+    } else {
+        solver.run_solver(&board.ignore_words).unwrap_or_default()
+    };
+    tidy_solutions(args, &mut solutions, frequencies);
+    Ok((puzzle, solutions))
+}
+
+// a board's record: a block of text, a line of JSON, or its rows of the
+// table
+fn print_board<W: Write>(
+    out: &mut W,
+    args: &Args,
+    board: &Board,
+    found_solutions: Result<(Puzzle, Vec<Solution>), LbError>,
+    frequencies: Option<&Frequencies>,
+    definitions: Option<&Definitions>,
+) -> io::Result<()> {
+    let Board {
+        groups,
+        ignore_words,
+    } = board;
+    match (args.format, found_solutions) {
+        (Format::Json, Ok((puzzle, solutions))) => writeln!(
+            out,
+            "{}",
+            solutions_json(
+                groups,
+                ignore_words,
+                &solutions,
+                frequencies,
+                definitions,
+                args.explain.then_some(&puzzle)
+            )
+        ),
+        (Format::Json, Err(err)) => writeln!(
+            out,
+            "{}",
+            json::Value::object()
+                .with("board", groups.clone())
+                .with("ignore", ignore_words.clone())
+                .with("error", err.to_string())
+        ),
+        (Format::Csv | Format::Tsv, Ok((_, solutions))) => {
+            let separator = args.format.separator().unwrap_or(',');
+            print_table_rows(out, separator, groups, &solutions)
+        }
+        // a table has nowhere to put errors
+        (Format::Csv | Format::Tsv, Err(err)) => {
+            eprintln!("Error: {} ({})", err, groups.join(" "));
+            Ok(())
+        }
+        (Format::Text, found_solutions) => {
+            writeln!(out, "\nGroups: {:?}", groups)?;
+            writeln!(out, "Ignore: {:?}", ignore_words)?;
+            match found_solutions {
+                Ok((_, solutions)) if solutions.is_empty() => writeln!(out, "No solution found"),
+                Ok((puzzle, solutions)) => {
+                    for solution in solutions {
+                        print_solution(out, args, &puzzle, &solution, definitions)?;
+                    }
+                    Ok(())
+                }
+                Err(err) => writeln!(out, "Error: {}", err),
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeSet;
+
+use crate::{json, LbError};
+
+// the NYT's other letter game: words of 4 or more letters made from a set
+// of letters, as many times each as they like, that all use the letter in
+// the middle. a word that uses every letter is a pangram
+#[derive(Debug, Clone)]
+pub struct SpellingBee {
+    center: char,
+    // every letter that can be used, the center one included
+    letters: BTreeSet<char>,
+}
+
+// the shortest word the game accepts
+const MIN_WORD_LENGTH: usize = 4;
+
+impl SpellingBee {
+    // the other letters can be given with or without the center one, and
+    // in any case
+    pub fn new(center: &str, letters: &str) -> Result<SpellingBee, LbError> {
+        let mut center_letters = center.chars().flat_map(char::to_lowercase);
+        let center = match (center_letters.next(), center_letters.next()) {
+            (Some(c), None) if c.is_alphabetic() => c,
+            _ => {
+                return Err(LbError::InvalidBee(format!(
+                    "the center must be a single letter, got {:?}",
+                    center
+                )))
+            }
+        };
+        let mut all_letters: BTreeSet<char> =
+            letters.chars().flat_map(char::to_lowercase).collect();
+        if let Some(c) = all_letters.iter().find(|c| !c.is_alphabetic()) {
+            return Err(LbError::InvalidBee(format!("{:?} is not a letter", c)));
+        }
+        all_letters.insert(center);
+        if all_letters.len() < 2 {
+            return Err(LbError::InvalidBee(
+                "there need to be some letters around the center one".to_string(),
+            ));
+        }
+        Ok(SpellingBee {
+            center,
+            letters: all_letters,
+        })
+    }
+
+    pub fn center(&self) -> char {
+        self.center
+    }
+
+    pub fn letters(&self) -> &BTreeSet<char> {
+        &self.letters
+    }
+
+    // whether the game would take the word
+    pub fn accepts(&self, word: &str) -> bool {
+        word.chars().count() >= MIN_WORD_LENGTH
+            && word.contains(self.center)
+            && word.chars().all(|c| self.letters.contains(&c))
+    }
+
+    pub fn is_pangram(&self, word: &str) -> bool {
+        self.letters.iter().all(|&c| word.contains(c))
+    }
+
+    // the words from the word list the game accepts, highest scoring first
+    // and alphabetically after that
+    pub fn words(&self, dictionary: &[String]) -> Vec<BeeWord> {
+        let mut words: Vec<BeeWord> = dictionary
+            .iter()
+            .filter(|word| self.accepts(word))
+            .map(|word| self.score(word))
+            .collect();
+        words.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.word.cmp(&b.word)));
+        words.dedup_by(|a, b| a.word == b.word);
+        words
+    }
+
+    // a 4 letter word scores 1, longer words a point a letter, and a
+    // pangram 7 more on top
+    fn score(&self, word: &str) -> BeeWord {
+        let length = word.chars().count();
+        let pangram = self.is_pangram(word);
+        let mut score = if length == MIN_WORD_LENGTH { 1 } else { length };
+        if pangram {
+            score += 7;
+        }
+        BeeWord {
+            word: word.to_string(),
+            score,
+            pangram,
+        }
+    }
+
+    pub fn to_json(&self) -> json::Value {
+        let letters: Vec<char> = self.letters.iter().copied().collect();
+        json::Value::object()
+            .with("center", self.center)
+            .with("letters", letters)
+    }
+}
+
+// a word the game accepts, and what it scores
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeeWord {
+    pub word: String,
+    pub score: usize,
+    pub pangram: bool,
+}
+
+impl BeeWord {
+    pub fn to_json(&self) -> json::Value {
+        json::Value::object()
+            .with("word", self.word.as_str())
+            .with("score", self.score)
+            .with("pangram", self.pangram)
+    }
+}
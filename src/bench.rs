@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use lbsolver::{json, Puzzle, Solver};
+
+use crate::{configure, Args, Format, WordRestrictions};
+
+// boards to time, from one that's solved in two words straight away to one
+// that has no solution at all, so the whole search space gets looked at
+const BOARDS: &[(&str, [&str; 4])] = &[
+    ("easy", ["rme", "wca", "ots", "ihn"]),
+    ("medium", ["abc", "def", "ghi", "jkl"]),
+    ("hard", ["ulq", "aic", "npo", "tkj"]),
+    ("four-words", ["xqj", "vkz", "eai", "ous"]),
+    ("unsolvable", ["qjx", "zvk", "ieo", "bgp"]),
+];
+
+// how long each part of solving a board took
+struct Timing {
+    name: &'static str,
+    board: Vec<String>,
+    words: usize,
+    filter: Duration,
+    search: Duration,
+    solutions: usize,
+    word_count: Option<usize>,
+}
+
+// time reading the word lists, then filtering them for each board and
+// searching it, and print them side by side. the cache is skipped so the
+// word lists are read the slow way every time. names pick which boards to
+// run, all of them when there are none
+pub fn run(
+    args: &Args,
+    filenames: &[PathBuf],
+    restrictions: &WordRestrictions,
+    names: &[String],
+) -> Result<(), String> {
+    for name in names {
+        if !BOARDS.iter().any(|&(board, _)| board == name) {
+            let known: Vec<&str> = BOARDS.iter().map(|&(board, _)| board).collect();
+            return Err(format!(
+                "there's no bench board called {}, expected one of {}",
+                name,
+                known.join(", ")
+            ));
+        }
+    }
+
+    let started = Instant::now();
+    let mut lists = Vec::new();
+    for filename in filenames {
+        let words = args
+            .dictionary_filter
+            .read_word_list(filename)
+            .map_err(|err| format!("{} ({})", err, filename.display()))?;
+        lists.push(words);
+    }
+    let mut words = lbsolver::merge_word_lists(lists);
+    restrictions.apply(&mut words);
+    let load = started.elapsed();
+
+    let mut timings = Vec::new();
+    for &(name, sides) in BOARDS {
+        if !names.is_empty() && !names.iter().any(|wanted| wanted == name) {
+            continue;
+        }
+        let board: Vec<String> = sides.iter().map(|side| side.to_string()).collect();
+        let puzzle = Puzzle::new(&board).map_err(|err| err.to_string())?;
+
+        let started = Instant::now();
+        let mut solver = Solver::from_words(puzzle, &words);
+        configure(&mut solver, args);
+        let filter = started.elapsed();
+
+        let started = Instant::now();
+        let solutions = solver
+            .run_solver(&args.ignore_words)
+            .map_err(|err| err.to_string())?;
+        let search = started.elapsed();
+
+        timings.push(Timing {
+            name,
+            board,
+            words: solver.dictionary().len(),
+            filter,
+            search,
+            solutions: solutions.len(),
+            word_count: solutions.first().map(|solution| solution.word_count()),
+        });
+    }
+
+    if args.format == Format::Json {
+        let boards: Vec<json::Value> = timings
+            .iter()
+            .map(|timing| {
+                json::Value::object()
+                    .with("name", timing.name)
+                    .with("board", timing.board.clone())
+                    .with("words", timing.words)
+                    .with("filter_ms", milliseconds(timing.filter))
+                    .with("search_ms", milliseconds(timing.search))
+                    .with("solutions", timing.solutions)
+                    .with("word_count", timing.word_count)
+            })
+            .collect();
+        let result = json::Value::object()
+            .with("dictionary_words", words.len())
+            .with("load_ms", milliseconds(load))
+            .with("boards", boards);
+        println!("{}", result.to_pretty_string());
+        return Ok(());
+    }
+
+    println!("Read {} words in {:.1?}\n", words.len(), load);
+    println!(
+        "{:<12} {:<16} {:>6} {:>10} {:>10} {:>10}",
+        "board", "letters", "words", "filter", "search", "solution"
+    );
+    for timing in &timings {
+        let solution = match timing.word_count {
+            Some(word_count) => format!("{} words", word_count),
+            None => "none".to_string(),
+        };
+        println!(
+            "{:<12} {:<16} {:>6} {:>10} {:>10} {:>10}",
+            timing.name,
+            timing.board.join(" "),
+            timing.words,
+            format!("{:.1?}", timing.filter),
+            format!("{:.1?}", timing.search),
+            solution
+        );
+    }
+    Ok(())
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 10_000.0).round() / 10.0
+}
@@ -0,0 +1,141 @@
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::{log, read_word_list, CompiledDictionary, LbError, Puzzle, Solver};
+
+// the start of every cache file, bumped if the layout changes
+const MAGIC: &[u8; 8] = b"LBCACHE2";
+
+// keeps preprocessed copies of word lists so they don't have to be read
+// and filtered from scratch on every run: the whole list, and the words
+// that fit each board that's been solved
+//
+// a cache file is the magic bytes, the size and modified time of the word
+// list it came from, then the words one per line. if the word list has
+// changed since, the cache file is ignored and written again
+#[derive(Debug, Clone)]
+pub struct DictionaryCache {
+    dir: PathBuf,
+}
+
+impl DictionaryCache {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> DictionaryCache {
+        DictionaryCache { dir: dir.into() }
+    }
+
+    // $XDG_CACHE_HOME/lbsolver, or ~/.cache/lbsolver
+    pub fn user() -> Option<DictionaryCache> {
+        let base = env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+        Some(DictionaryCache::new(base.join("lbsolver")))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // the same as `read_word_list`, from the cache when it's there
+    pub fn read_word_list<P: AsRef<Path>>(&self, path: P) -> Result<Vec<String>, LbError> {
+        let path = path.as_ref();
+        // a compiled list is already quicker to load than a cached copy
+        if CompiledDictionary::is_compiled_file(path) {
+            return read_word_list(path);
+        }
+        let source = Source::of(path)?;
+        let cache_file = self.dir.join(format!("words-{:016x}.bin", source.key));
+        if let Some(words) = source.read(&cache_file) {
+            log::debug!("read the word list from {}", cache_file.display());
+            return Ok(words);
+        }
+        let words = read_word_list(path)?;
+        source.write(&cache_file, &words);
+        Ok(words)
+    }
+
+    // the same as `Solver::open`, starting from the words that fit the
+    // board when they're already in the cache
+    pub fn open_solver<P: AsRef<Path>>(&self, puzzle: Puzzle, path: P) -> Result<Solver, LbError> {
+        let path = path.as_ref();
+        if CompiledDictionary::is_compiled_file(path) {
+            return Solver::open(puzzle, path);
+        }
+        let source = Source::of(path)?;
+
+        // the order of the sides doesn't change which words fit
+        let mut groups: Vec<&Vec<char>> = puzzle.letter_groups().iter().collect();
+        groups.sort();
+        let mut hasher = DefaultHasher::new();
+        (source.key, groups).hash(&mut hasher);
+        let cache_file = self.dir.join(format!("board-{:016x}.bin", hasher.finish()));
+
+        if let Some(words) = source.read(&cache_file) {
+            log::debug!("read the board's words from {}", cache_file.display());
+            return Ok(Solver::from_words(puzzle, words));
+        }
+        let solver = Solver::from_words(puzzle, self.read_word_list(path)?);
+        source.write(&cache_file, solver.dictionary());
+        Ok(solver)
+    }
+}
+
+// a version of a word list: where it is, its size and when it last changed
+struct Source {
+    key: u64,
+    header: Vec<u8>,
+}
+
+impl Source {
+    fn of(path: &Path) -> io::Result<Source> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_nanos() as u64);
+
+        let mut hasher = DefaultHasher::new();
+        fs::canonicalize(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .hash(&mut hasher);
+
+        let mut header = MAGIC.to_vec();
+        header.extend(metadata.len().to_le_bytes());
+        header.extend(modified.to_le_bytes());
+        Ok(Source {
+            key: hasher.finish(),
+            header,
+        })
+    }
+
+    // the words in a cache file, if it's there and up to date
+    fn read(&self, cache_file: &Path) -> Option<Vec<String>> {
+        let mut bytes = Vec::new();
+        File::open(cache_file).ok()?.read_to_end(&mut bytes).ok()?;
+        let words = std::str::from_utf8(bytes.strip_prefix(self.header.as_slice())?).ok()?;
+        if words.is_empty() {
+            return Some(Vec::new());
+        }
+        Some(words.split('\n').map(str::to_string).collect())
+    }
+
+    // a cache is only ever a shortcut, so failing to write one is ignored.
+    // it's written to one side and moved into place so a run that's
+    // stopped halfway can't leave half a file behind
+    fn write(&self, cache_file: &Path, words: &[String]) {
+        let mut bytes = self.header.clone();
+        bytes.extend(words.join("\n").into_bytes());
+        let partial = cache_file.with_extension("partial");
+        let _ = cache_file
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&partial, bytes))
+            .and_then(|_| fs::rename(&partial, cache_file));
+    }
+}
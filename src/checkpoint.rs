@@ -0,0 +1,203 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::{log, LbError, Solution, Solutions, State, CHECKPOINT_INTERVAL};
+
+// a search's state saved to a file: the solutions found so far, the chains
+// still to look at and the ones put to one side for the next depth. it's a
+// run of little endian numbers after a header, and starts with a
+// fingerprint of the board, the words and the ignore words, so it can't be
+// resumed by a different search. the number on the end goes up whenever
+// what's saved changes, e.g. how the heuristic counts
+const MAGIC: &[u8; 8] = b"LBCHECK2";
+
+impl Solutions<'_> {
+    pub(crate) fn found_solutions(&self) -> Vec<Solution> {
+        self.found
+            .iter()
+            .map(|chain| {
+                let words = chain
+                    .iter()
+                    .map(|&i| self.solver.words.dictionary[i].clone())
+                    .collect();
+                Solution::new(words)
+            })
+            .collect()
+    }
+
+    // a search that can't save its state carries on regardless, it just
+    // can't be resumed from here
+    pub(crate) fn save_checkpoint(&mut self) {
+        let path = match &self.solver.checkpoint {
+            Some(path) => path,
+            None => return,
+        };
+        match self.write_checkpoint(path) {
+            Ok(()) => log::info!(
+                "saved the search to {} after expanding {} chains",
+                path.display(),
+                self.nodes_expanded
+            ),
+            Err(err) => log::info!("could not save the search to {}: {}", path.display(), err),
+        }
+        self.next_checkpoint = Some(Instant::now() + CHECKPOINT_INTERVAL);
+    }
+
+    // to a temporary file that's renamed over the last one, so there's
+    // always a whole checkpoint to resume from
+    fn write_checkpoint(&self, path: &Path) -> io::Result<()> {
+        let mut temporary = path.as_os_str().to_os_string();
+        temporary.push(".tmp");
+        let mut out = BufWriter::new(File::create(&temporary)?);
+        out.write_all(MAGIC)?;
+        write_u64(&mut out, self.fingerprint())?;
+        for number in [
+            self.depth,
+            self.max_words,
+            self.nodes_expanded,
+            self.best_coverage,
+        ] {
+            write_u64(&mut out, number as u64)?;
+        }
+        write_u64(&mut out, self.found.len() as u64)?;
+        for chain in &self.found {
+            write_chain(&mut out, chain)?;
+        }
+        // the queue in the heap's own order, so it comes back exactly as
+        // it was and the search carries on the same as it would have
+        for states in [self.priority_queue.as_slice(), &self.deferred] {
+            write_u64(&mut out, states.len() as u64)?;
+            for state in states {
+                write_chain(&mut out, &state.chain)?;
+                for number in [
+                    state.used_words,
+                    state.coverage,
+                    state.missing_required as u64,
+                    state.cost as u64,
+                    state.heuristic as u64,
+                    state.estimate,
+                    state.score,
+                ] {
+                    write_u64(&mut out, number)?;
+                }
+            }
+        }
+        out.into_inner()?.sync_all()?;
+        fs::rename(&temporary, path)
+    }
+
+    pub(crate) fn load_checkpoint(&mut self, path: &Path) -> Result<(), LbError> {
+        File::open(path)
+            .and_then(|file| self.read_checkpoint(&mut BufReader::new(file)))
+            .map_err(|err| LbError::InvalidCheckpoint(format!("{} ({})", path.display(), err)))
+    }
+
+    fn read_checkpoint<R: Read>(&mut self, source: &mut R) -> io::Result<()> {
+        let mut magic = [0; 8];
+        source.read_exact(&mut magic)?;
+        if magic.starts_with(b"LBCHECK") && &magic != MAGIC {
+            return Err(invalid("it was saved by another version of lbsolver"));
+        }
+        if &magic != MAGIC {
+            return Err(invalid("it isn't a checkpoint"));
+        }
+        if read_u64(source)? != self.fingerprint() {
+            return Err(invalid(
+                "it's for a different board, word list or ignore words",
+            ));
+        }
+        self.depth = read_u64(source)? as usize;
+        self.max_words = read_u64(source)? as usize;
+        self.nodes_expanded = read_u64(source)? as usize;
+        self.best_coverage = read_u64(source)? as usize;
+
+        let words = self.solver.words.dictionary.len();
+        self.found = (0..read_u64(source)?)
+            .map(|_| read_chain(source, words))
+            .collect::<io::Result<_>>()?;
+        let mut states = [Vec::new(), Vec::new()];
+        for states in &mut states {
+            for _ in 0..read_u64(source)? {
+                let chain = read_chain(source, words)?;
+                let mut numbers = [0; 7];
+                for number in &mut numbers {
+                    *number = read_u64(source)?;
+                }
+                let [used_words, coverage, missing_required, cost, heuristic, estimate, score] =
+                    numbers;
+                states.push(State {
+                    chain,
+                    used_words,
+                    coverage,
+                    missing_required: missing_required as usize,
+                    cost: cost as usize,
+                    heuristic: heuristic as usize,
+                    estimate,
+                    score,
+                });
+            }
+        }
+        let [queue, deferred] = states;
+        self.priority_queue = queue.into();
+        self.deferred = deferred;
+        Ok(())
+    }
+
+    // FNV-1a over everything that changes which chains the search makes.
+    // it's written out by hand so it's the same from one build to the next
+    fn fingerprint(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut add = |bytes: &[u8]| {
+            for &byte in bytes {
+                hash = (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+            }
+        };
+        add(self.solver.puzzle.to_string().as_bytes());
+        for word in self.solver.words.dictionary.iter() {
+            add(word.as_bytes());
+            add(&[0xff]);
+        }
+        for (&ignored, &required) in self.ignored.iter().zip(&self.required) {
+            add(&[u8::from(ignored), u8::from(required)]);
+        }
+        add(self.solver.start_word.as_deref().unwrap_or("").as_bytes());
+        add(&self.solver.weight.to_bits().to_le_bytes());
+        add(&[self.solver.heuristic as u8]);
+        hash
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_u64<W: Write>(out: &mut W, number: u64) -> io::Result<()> {
+    out.write_all(&number.to_le_bytes())
+}
+
+fn read_u64<R: Read>(source: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    source.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn write_chain<W: Write>(out: &mut W, chain: &[usize]) -> io::Result<()> {
+    write_u64(out, chain.len() as u64)?;
+    for &i in chain {
+        write_u64(out, i as u64)?;
+    }
+    Ok(())
+}
+
+// the words are checked against the dictionary, which the fingerprint
+// should already have made sure of
+fn read_chain<R: Read>(source: &mut R, words: usize) -> io::Result<Vec<usize>> {
+    (0..read_u64(source)?)
+        .map(|_| match read_u64(source)? as usize {
+            i if i < words => Ok(i),
+            _ => Err(invalid("it has a word that isn't in the word list")),
+        })
+        .collect()
+}
@@ -0,0 +1,37 @@
+use std::process::Command;
+
+// the programs that print the clipboard on each platform, tried in turn
+// so there's no need for a clipboard library: macOS, Wayland, X11 (two
+// ways) and Windows
+const PASTE_COMMANDS: &[(&str, &[&str])] = &[
+    ("pbpaste", &[]),
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-o"]),
+    ("xsel", &["--clipboard", "--output"]),
+    ("powershell", &["-NoProfile", "-Command", "Get-Clipboard"]),
+];
+
+// what's on the clipboard, from the first paste command that works
+pub fn read() -> Result<String, String> {
+    for (program, args) in PASTE_COMMANDS {
+        match Command::new(program).args(*args).output() {
+            Ok(output) if output.status.success() => {
+                return Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+            }
+            _ => continue,
+        }
+    }
+    Err("could not read the clipboard, install wl-paste, xclip or xsel".to_string())
+}
+
+// the board arguments in text copied from the clipboard. the NYT page has
+// each letter on its own, so copying the board gives the letters one per
+// line, and those are put back together as one board
+pub fn board_arguments(text: &str) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() > 1 && words.iter().all(|word| word.chars().count() == 1) {
+        vec![words.concat()]
+    } else {
+        words.into_iter().map(str::to_string).collect()
+    }
+}
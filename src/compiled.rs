@@ -0,0 +1,306 @@
+use std::fs::{self, File};
+use std::io::Read;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::dictionary::{ascii_bit, ascii_mask};
+use crate::mmap::Mapping;
+use crate::{log, lowercase, LbError, Puzzle};
+
+// the start of every compiled word list, bumped if the layout changes
+pub(crate) const MAGIC: &[u8; 8] = b"LBDICT01";
+
+// the usual file extension, e.g. words.lbdict
+pub const EXTENSION: &str = "lbdict";
+
+// a word's first or last letter, a to z, and one more for anything else
+const BUCKETS: usize = 27;
+
+// the mask stored for a word with letters outside a to z
+const NO_MASK: u32 = u32::MAX;
+
+// a word list compiled ahead of time, so it's read without splitting lines
+// and the letter masks and start and end indexes are already worked out.
+// everything is a little endian u32 after the magic, so the file can be
+// used as it sits in memory:
+//
+//   the number of words, and the length of the words' text
+//   where each word starts in the text, and one more for where it ends
+//   each word's a to z letters as bits, u32::MAX if it has others
+//   the start index: where each letter's words start in the list after
+//     it, then every word's number grouped by first letter
+//   the end index, the same grouped by last letter
+//   the words' text, one after another
+//
+// the words are kept as they were written, so proper nouns and
+// abbreviations can still be filtered out, and in the order they were in.
+// an opened file is mapped into memory rather than read, and the words are
+// borrowed from it, so only the ones that are kept get copied
+#[derive(Debug, Clone)]
+pub struct CompiledDictionary {
+    bytes: Bytes,
+    count: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Bytes {
+    Read(Arc<[u8]>),
+    Mapped(Arc<Mapping>),
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Bytes::Read(bytes) => bytes,
+            Bytes::Mapped(mapping) => mapping,
+        }
+    }
+}
+
+impl CompiledDictionary {
+    // whether the bytes are a compiled word list rather than a text one
+    pub fn is_compiled(bytes: &[u8]) -> bool {
+        bytes.starts_with(MAGIC)
+    }
+
+    // whether the file is a compiled word list, without reading the rest
+    // of it
+    pub fn is_compiled_file<P: AsRef<Path>>(path: P) -> bool {
+        let mut start = [0; MAGIC.len()];
+        File::open(path)
+            .and_then(|mut file| file.read_exact(&mut start))
+            .is_ok_and(|_| CompiledDictionary::is_compiled(&start))
+    }
+
+    // mapped into memory where that's possible, otherwise read
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<CompiledDictionary, LbError> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        match Mapping::open(&file) {
+            Ok(mapping) => {
+                log::debug!("mapped {} into memory", path.display());
+                CompiledDictionary::with_bytes(Bytes::Mapped(Arc::new(mapping)))
+            }
+            Err(err) => {
+                log::debug!("could not map {} ({}), reading it", path.display(), err);
+                CompiledDictionary::from_bytes(fs::read(path)?)
+            }
+        }
+    }
+
+    // checks the whole layout up front, so looking words up afterwards
+    // can't go out of bounds
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<CompiledDictionary, LbError> {
+        CompiledDictionary::with_bytes(Bytes::Read(bytes.into()))
+    }
+
+    fn with_bytes(bytes: Bytes) -> Result<CompiledDictionary, LbError> {
+        let invalid = |message: &str| LbError::InvalidDictionary(message.to_string());
+        if !CompiledDictionary::is_compiled(&bytes) {
+            return Err(invalid("it doesn't start with the right bytes"));
+        }
+        if bytes.len() < MAGIC.len() + 8 {
+            return Err(invalid("it's cut short"));
+        }
+        let mut dictionary = CompiledDictionary { bytes, count: 0 };
+        let count = dictionary.number(0);
+        let text_length = dictionary.number(1);
+        dictionary.count = count;
+        if dictionary.text_start().checked_add(text_length) != Some(dictionary.bytes.len()) {
+            return Err(invalid("it's cut short"));
+        }
+
+        let text = std::str::from_utf8(dictionary.text())
+            .map_err(|_| invalid("a word isn't valid UTF-8"))?;
+        let mut last = 0;
+        for i in 0..=count {
+            let offset = dictionary.offset(i);
+            if offset < last || !text.is_char_boundary(offset) {
+                return Err(invalid("a word's position is wrong"));
+            }
+            last = offset;
+        }
+        if last != text_length {
+            return Err(invalid("a word's position is wrong"));
+        }
+        for index in [dictionary.start_index(), dictionary.end_index()] {
+            let mut last = 0;
+            for bucket in 0..=BUCKETS {
+                let boundary = dictionary.number(index + bucket);
+                if boundary < last {
+                    return Err(invalid("an index is out of order"));
+                }
+                last = boundary;
+            }
+            let entries = index + BUCKETS + 1;
+            if last != count || (0..count).any(|i| dictionary.number(entries + i) >= count) {
+                return Err(invalid("an index points outside the list"));
+            }
+        }
+        Ok(dictionary)
+    }
+
+    // compile a word list, e.g. to write out with `fs::write`
+    pub fn compile<S: AsRef<str>>(words: &[S]) -> Vec<u8> {
+        let count = words.len();
+        let mut text = Vec::new();
+        let mut offsets = Vec::with_capacity(count + 1);
+        let mut masks = Vec::with_capacity(count);
+        let mut starts = vec![Vec::new(); BUCKETS];
+        let mut ends = vec![Vec::new(); BUCKETS];
+        for (i, word) in words.iter().enumerate() {
+            let word = word.as_ref();
+            offsets.push(text.len());
+            text.extend_from_slice(word.as_bytes());
+            let lower = lowercase(word);
+            masks.push(ascii_mask(&lower).unwrap_or(NO_MASK) as usize);
+            starts[bucket(lower.chars().next())].push(i);
+            ends[bucket(lower.chars().next_back())].push(i);
+        }
+        offsets.push(text.len());
+
+        let mut numbers = vec![count, text.len()];
+        numbers.extend(offsets);
+        numbers.extend(masks);
+        for index in [starts, ends] {
+            let mut boundary = 0;
+            numbers.push(boundary);
+            for bucket in &index {
+                boundary += bucket.len();
+                numbers.push(boundary);
+            }
+            numbers.extend(index.into_iter().flatten());
+        }
+
+        let mut bytes = MAGIC.to_vec();
+        for number in numbers {
+            bytes.extend((number as u32).to_le_bytes());
+        }
+        bytes.extend(text);
+        bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // the word as it was written in the list it was compiled from
+    pub fn word(&self, i: usize) -> &str {
+        let text = &self.text()[self.offset(i)..self.offset(i + 1)];
+        // checked in `from_bytes`
+        std::str::from_utf8(text).unwrap_or_default()
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = &str> + '_ {
+        (0..self.count).map(|i| self.word(i))
+    }
+
+    // the words that only use the board's letters, going by their masks.
+    // a word with letters outside a to z is let through to be checked the
+    // slow way, the same as `Dictionary::solver_for`
+    pub fn fitting<'a>(&'a self, puzzle: &Puzzle) -> impl Iterator<Item = &'a str> + 'a {
+        let board = puzzle
+            .available_chars()
+            .iter()
+            .filter_map(|&c| ascii_bit(c))
+            .fold(0, |mask, bit| mask | bit);
+        (0..self.count)
+            .filter(move |&i| self.letter_mask(i).is_none_or(|mask| mask & !board == 0))
+            .map(|i| self.word(i))
+    }
+
+    // the a to z letters the lowercased word uses as bits, none if it has
+    // other letters too
+    pub fn letter_mask(&self, i: usize) -> Option<u32> {
+        let mask = self.number(2 + self.count + 1 + i) as u32;
+        (mask != NO_MASK).then_some(mask)
+    }
+
+    // the numbers of the words starting with the letter, in list order
+    pub fn starting_with(&self, c: char) -> impl Iterator<Item = usize> + '_ {
+        self.indexed(self.start_index(), c)
+    }
+
+    // the numbers of the words ending with the letter, in list order
+    pub fn ending_with(&self, c: char) -> impl Iterator<Item = usize> + '_ {
+        self.indexed(self.end_index(), c)
+    }
+
+    fn indexed(&self, index: usize, c: char) -> impl Iterator<Item = usize> + '_ {
+        let bucket = bucket(c.to_lowercase().next());
+        let entries = index + BUCKETS + 1;
+        (self.number(index + bucket)..self.number(index + bucket + 1))
+            .map(move |i| self.number(entries + i))
+    }
+
+    // the u32 at that position after the magic
+    fn number(&self, position: usize) -> usize {
+        let at = MAGIC.len() + position * 4;
+        let mut number = [0; 4];
+        number.copy_from_slice(&self.bytes[at..at + 4]);
+        u32::from_le_bytes(number) as usize
+    }
+
+    fn offset(&self, i: usize) -> usize {
+        self.number(2 + i)
+    }
+
+    fn start_index(&self) -> usize {
+        2 + (self.count + 1) + self.count
+    }
+
+    fn end_index(&self) -> usize {
+        self.start_index() + BUCKETS + 1 + self.count
+    }
+
+    fn text_start(&self) -> usize {
+        MAGIC.len() + (self.end_index() + BUCKETS + 1 + self.count) * 4
+    }
+
+    fn text(&self) -> &[u8] {
+        &self.bytes[self.text_start()..]
+    }
+}
+
+fn bucket(c: Option<char>) -> usize {
+    match c {
+        Some(c) if c.is_ascii_lowercase() => (c as u8 - b'a') as usize,
+        _ => BUCKETS - 1,
+    }
+}
+
+// the entries in a word list as they're written, from a compiled list or
+// a text one with a word on each line
+pub fn read_entries<R: Read>(mut source: R) -> Result<Vec<String>, LbError> {
+    let mut bytes = Vec::new();
+    source.read_to_end(&mut bytes)?;
+    if CompiledDictionary::is_compiled(&bytes) {
+        let dictionary = CompiledDictionary::from_bytes(bytes)?;
+        log::debug!("read a compiled word list of {} words", dictionary.len());
+        return Ok(dictionary.words().map(str::to_string).collect());
+    }
+    // a line that isn't UTF-8 is skipped, like when a solver reads the list
+    let mut skipped = 0;
+    let mut entries = Vec::new();
+    for line in bytes.split(|&byte| byte == b'\n') {
+        match std::str::from_utf8(line) {
+            Ok(line) => entries.push(line.strip_suffix('\r').unwrap_or(line).to_string()),
+            Err(_) => skipped += 1,
+        }
+    }
+    // a list ending in a newline doesn't have another, empty, line after it
+    if bytes.last().is_none_or(|&byte| byte == b'\n') {
+        entries.pop();
+    }
+    if skipped > 0 {
+        log::info!("skipped {} lines that aren't valid UTF-8", skipped);
+    }
+    Ok(entries)
+}
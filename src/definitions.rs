@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+use crate::LbError;
+
+// a short meaning for words, to help judge whether a word in a solution
+// is one people would actually play
+//
+// the file either has a word per line followed by what it means, e.g.
+// "abaca a plant of the Philippines", or is one of WordNet's data files
+// (data.noun, data.verb, ...), where each line lists the words in a set
+// of synonyms and their gloss comes after a |
+#[derive(Debug, Default, Clone)]
+pub struct Definitions {
+    glosses: HashMap<String, String>,
+}
+
+impl Definitions {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Definitions, LbError> {
+        let source = File::open(path)?;
+        Definitions::from_reader(io::BufReader::new(source))
+    }
+
+    pub fn from_reader<R: BufRead>(source: R) -> Result<Definitions, LbError> {
+        let mut definitions = Definitions::default();
+        for line in source.lines() {
+            let line = line?;
+            // WordNet's files start with the licence, indented
+            if line.starts_with(char::is_whitespace) {
+                continue;
+            }
+            match wordnet_line(&line) {
+                Some((words, gloss)) => {
+                    for word in words {
+                        definitions.add(word, gloss);
+                    }
+                }
+                None => {
+                    if let Some((word, gloss)) = line.trim().split_once(char::is_whitespace) {
+                        definitions.add(word, gloss);
+                    }
+                }
+            }
+        }
+        Ok(definitions)
+    }
+
+    // the first meaning listed for a word is kept, WordNet lists the most
+    // common one first
+    fn add(&mut self, word: &str, gloss: &str) {
+        let gloss = gloss.trim();
+        if !gloss.is_empty() {
+            self.glosses
+                .entry(word.to_lowercase())
+                .or_insert_with(|| gloss.to_string());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.glosses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glosses.is_empty()
+    }
+
+    pub fn gloss(&self, word: &str) -> Option<&str> {
+        self.glosses.get(word).map(String::as_str)
+    }
+}
+
+// the words and gloss in a line of a WordNet data file, e.g.
+// "02084071 05 n 03 dog 0 domestic_dog 0 Canis_familiaris 0 023 @ ... | a
+// member of the genus Canis ...; \"the dog barked all night\"". the gloss
+// is cut down to the definition, leaving out the examples after it
+fn wordnet_line(line: &str) -> Option<(Vec<&str>, &str)> {
+    let (data, gloss) = line.split_once(" | ")?;
+    let mut fields = data.split_whitespace();
+    let offset = fields.next()?;
+    if !offset.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    // the lexicographer file number and the part of speech
+    fields.next()?;
+    fields.next()?;
+    let word_count = usize::from_str_radix(fields.next()?, 16).ok()?;
+    let mut words = Vec::new();
+    for _ in 0..word_count {
+        let word = fields.next()?;
+        // adjectives can be marked with where they go, e.g. "galore(ip)"
+        words.push(word.split('(').next().unwrap_or(word));
+        // the lex id
+        fields.next()?;
+    }
+    let gloss = gloss.split(';').next().unwrap_or(gloss);
+    Some((words, gloss))
+}
@@ -0,0 +1,194 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use lbsolver::json;
+use lbsolver::{compiled, CompiledDictionary, Puzzle, Solver};
+
+use crate::output::Output;
+use crate::{Args, Format};
+
+// what to do with a word list, for dict
+pub enum DictAction {
+    // turn a word list into a compiled one that's quicker to load
+    Compile { input: PathBuf, output: PathBuf },
+    // say what's in the word lists, and with a board how many of the words
+    // fit it
+    Stats { board: Vec<String> },
+}
+
+impl DictAction {
+    pub fn parse(mut words: Vec<String>) -> Result<DictAction, String> {
+        if words.is_empty() {
+            return Err("dict needs compile or stats".to_string());
+        }
+        let action = words.remove(0);
+        match action.as_str() {
+            "compile" if words.is_empty() || words.len() > 2 => Err(
+                "dict compile needs the word list, and optionally the file to write".to_string(),
+            ),
+            // words.txt is compiled to words.lbdict unless it's given
+            "compile" => {
+                let input = PathBuf::from(&words[0]);
+                let output = words
+                    .get(1)
+                    .map_or_else(|| input.with_extension(compiled::EXTENSION), PathBuf::from);
+                Ok(DictAction::Compile { input, output })
+            }
+            "stats" => Ok(DictAction::Stats { board: words }),
+            _ => Err(format!(
+                "unknown dict action {}, expected compile or stats",
+                action
+            )),
+        }
+    }
+}
+
+pub fn compile(input: &Path, output: &Path) -> Result<(), String> {
+    // read as written, so the compiled list can still be filtered for
+    // proper nouns and abbreviations
+    let file =
+        File::open(input).map_err(|err| format!("could not read {} ({})", input.display(), err))?;
+    let words = compiled::read_entries(file)
+        .map_err(|err| format!("could not read {} ({})", input.display(), err))?;
+    let bytes = CompiledDictionary::compile(&words);
+    let mut out = Output::create(Some(output))
+        .map_err(|err| format!("could not write {} ({})", output.display(), err))?;
+    if let Err(err) = out.write_all(&bytes) {
+        out.abandon();
+        return Err(format!("could not write {} ({})", output.display(), err));
+    }
+    out.finish()
+        .map_err(|err| format!("could not write {} ({})", output.display(), err))?;
+    println!(
+        "Compiled {} words from {} into {} ({} bytes)",
+        words.len(),
+        input.display(),
+        output.display(),
+        bytes.len()
+    );
+    Ok(())
+}
+
+// how many words fit a board: the ones that only use its letters, and the
+// ones of those that can actually be played, never going from a side to
+// the same side
+struct BoardStats {
+    board: String,
+    letters_only: usize,
+    playable: usize,
+}
+
+// the word lists as they'd be used to solve a board, with the same options
+pub fn stats(args: &Args, board: &[String], words: &[String]) -> Result<(), String> {
+    let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+    // how many words start and end with each letter
+    let mut letters: BTreeMap<char, (usize, usize)> = BTreeMap::new();
+    for word in words {
+        *lengths.entry(word.chars().count()).or_default() += 1;
+        if let (Some(first), Some(last)) = (word.chars().next(), word.chars().next_back()) {
+            letters.entry(first).or_default().0 += 1;
+            letters.entry(last).or_default().1 += 1;
+        }
+    }
+    let board = if board.is_empty() {
+        None
+    } else {
+        Some(board_stats(args, board, words)?)
+    };
+
+    match args.format {
+        Format::Json => {
+            let lengths: Vec<json::Value> = lengths
+                .iter()
+                .map(|(&length, &count)| {
+                    json::Value::object()
+                        .with("length", length)
+                        .with("count", count)
+                })
+                .collect();
+            let letters: Vec<json::Value> = letters
+                .iter()
+                .map(|(&letter, &(starting, ending))| {
+                    json::Value::object()
+                        .with("letter", letter)
+                        .with("starting", starting)
+                        .with("ending", ending)
+                })
+                .collect();
+            let board = board.map(|board| {
+                json::Value::object()
+                    .with("board", board.board)
+                    .with("letters_only", board.letters_only)
+                    .with("playable", board.playable)
+            });
+            let result = json::Value::object()
+                .with("words", words.len())
+                .with("lengths", lengths)
+                .with("letters", letters)
+                .with("board", board);
+            println!("{}", result.to_pretty_string());
+        }
+        Format::Csv | Format::Tsv => {
+            let separator = args.format.separator().unwrap_or(',');
+            println!("{}", ["stat", "key", "count"].join(&separator.to_string()));
+            let row = |stat: &str, key: &str, count: usize| {
+                println!("{1}{0}{2}{0}{3}", separator, stat, key, count);
+            };
+            row("words", "", words.len());
+            for (length, &count) in &lengths {
+                row("length", &length.to_string(), count);
+            }
+            for (letter, &(starting, ending)) in &letters {
+                row("starting", &letter.to_string(), starting);
+                row("ending", &letter.to_string(), ending);
+            }
+            if let Some(board) = &board {
+                row("letters_only", &board.board, board.letters_only);
+                row("playable", &board.board, board.playable);
+            }
+        }
+        Format::Text => {
+            println!("{} words\n", words.len());
+            println!("Length  Words");
+            for (length, count) in &lengths {
+                println!("{:>6}  {:>5}", length, count);
+            }
+            println!("\nLetter  Starting  Ending");
+            for (letter, (starting, ending)) in &letters {
+                println!("{:>6}  {:>8}  {:>6}", letter, starting, ending);
+            }
+            if let Some(board) = &board {
+                println!(
+                    "\n{}: {} words only use its letters, {} of them can be played on it",
+                    board.board, board.letters_only, board.playable
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn board_stats(args: &Args, board: &[String], words: &[String]) -> Result<BoardStats, String> {
+    let groups = match board {
+        [board] => lbsolver::split_board(board, args.sides).map_err(|err| err.to_string())?,
+        _ => board.to_vec(),
+    };
+    let puzzle =
+        Puzzle::with_group_size(&groups, args.group_size).map_err(|err| err.to_string())?;
+    let letters_only = words
+        .iter()
+        .filter(|word| {
+            word.chars().count() >= 3 && word.chars().all(|c| puzzle.available_chars().contains(&c))
+        })
+        .count();
+    let solver = Solver::from_words(puzzle, words);
+    // a word that can be played more than one way is only counted once
+    let playable: HashSet<&String> = solver.dictionary().iter().collect();
+    Ok(BoardStats {
+        board: groups.join("-").to_lowercase(),
+        letters_only,
+        playable: playable.len(),
+    })
+}
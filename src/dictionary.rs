@@ -0,0 +1,90 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::{lowercase, read_word_list, LbError, Puzzle, Solver, WordFilter};
+
+// a word list read once and kept ready for many boards, so each board only
+// has to pick out the words that fit it. the words are lowercased, and
+// repeats and words shorter than 3 letters are dropped, once, up front.
+// each word also keeps the a to z letters it uses as bits, so most of the
+// words that don't fit a board are ruled out with a single comparison.
+// it's never changed once it's made, so a clone shares the words, e.g.
+// with threads solving other boards
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    words: Arc<[String]>,
+    // none for a word with letters outside a to z, which is checked the
+    // slow way
+    letter_masks: Arc<[Option<u32>]>,
+}
+
+impl Dictionary {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Dictionary, LbError> {
+        Ok(Dictionary::from_words(read_word_list(path)?))
+    }
+
+    // the same as `load`, only keeping the words the filter accepts
+    pub fn load_with<P: AsRef<Path>>(
+        path: P,
+        filter: &dyn WordFilter,
+    ) -> Result<Dictionary, LbError> {
+        let mut words = read_word_list(path)?;
+        words.retain(|word| filter.accept(word));
+        Ok(Dictionary::from_words(words))
+    }
+
+    pub fn from_words<I, S>(words: I) -> Dictionary
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let words = crate::merge_word_lists([words
+            .into_iter()
+            .map(|word| lowercase(word.as_ref()).into_owned())
+            .filter(|word| word.chars().count() >= 3)
+            .collect()]);
+        let letter_masks = words.iter().map(|word| ascii_mask(word)).collect();
+        Dictionary {
+            words: words.into(),
+            letter_masks,
+        }
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    // a solver for the board from the words that fit it, without reading
+    // the word list again
+    pub fn solver_for(&self, puzzle: &Puzzle) -> Solver {
+        let board = puzzle
+            .available_chars()
+            .iter()
+            .filter_map(|&c| ascii_bit(c))
+            .fold(0, |mask, bit| mask | bit);
+        let words = self
+            .words
+            .iter()
+            .zip(self.letter_masks.iter())
+            .filter(|(_, mask)| mask.is_none_or(|mask| mask & !board == 0))
+            .map(|(word, _)| word);
+        Solver::from_words(puzzle.clone(), words)
+    }
+}
+
+pub(crate) fn ascii_bit(c: char) -> Option<u32> {
+    c.is_ascii_lowercase().then(|| 1 << (c as u8 - b'a'))
+}
+
+pub(crate) fn ascii_mask(word: &str) -> Option<u32> {
+    word.chars()
+        .try_fold(0, |mask, c| ascii_bit(c).map(|bit| mask | bit))
+}
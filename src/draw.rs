@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use lbsolver::{Puzzle, Solution};
+
+// the board with the order a solution plays its letters in: each letter is
+// followed by the steps it's played at, counting the letter joining two
+// words once, the same way the game draws the line from letter to letter.
+// the sides are laid out like the game's square, top, right, bottom then
+// left, and any other number of sides gets a line each
+pub fn board(puzzle: &Puzzle, solution: &Solution) -> Vec<String> {
+    let mut steps: HashMap<char, Vec<usize>> = HashMap::new();
+    let mut played = 0;
+    let mut word_steps = Vec::new();
+    for (i, word) in solution.words.iter().enumerate() {
+        let first = played.max(1);
+        for c in word.chars().skip(usize::from(i > 0)) {
+            played += 1;
+            steps.entry(c).or_default().push(played);
+        }
+        word_steps.push(format!("{} {}-{}", word, first, played));
+    }
+
+    let label = |c: char| match steps.get(&c) {
+        Some(steps) => {
+            let steps: Vec<String> = steps.iter().map(usize::to_string).collect();
+            format!("{} {}", c, steps.join(","))
+        }
+        None => c.to_string(),
+    };
+    let sides: Vec<Vec<String>> = puzzle
+        .letter_groups()
+        .iter()
+        .map(|side| side.iter().map(|&c| label(c)).collect())
+        .collect();
+
+    let mut lines = if sides.len() == 4 {
+        square(&sides)
+    } else {
+        sides
+            .iter()
+            .enumerate()
+            .map(|(i, side)| format!("  side {}: {}", i + 1, side.join("   ")))
+            .collect()
+    };
+    lines.push(String::new());
+    lines.push(format!("  {}", word_steps.join(", ")));
+    lines
+}
+
+fn square(sides: &[Vec<String>]) -> Vec<String> {
+    let widest = |side: &[String]| {
+        side.iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0)
+    };
+    let column = widest(&sides[0]).max(widest(&sides[2])) + 2;
+    let left = widest(&sides[3]);
+    let width = column * sides[0].len().max(sides[2].len()) + 1;
+    let row = |side: &[String]| {
+        let labels: String = side
+            .iter()
+            .map(|label| format!("{:<1$}", label, column))
+            .collect();
+        format!("{}{}", " ".repeat(left + 5), labels.trim_end())
+    };
+    let edge = format!("{}+{}+", " ".repeat(left + 3), "-".repeat(width));
+
+    let mut lines = vec![row(&sides[0]), edge.clone()];
+    for (left_label, right_label) in sides[3].iter().zip(&sides[1]) {
+        lines.push(format!(
+            "  {:>3$} |{}| {}",
+            left_label,
+            " ".repeat(width),
+            right_label,
+            left
+        ));
+    }
+    lines.push(edge);
+    lines.push(row(&sides[2]));
+    lines
+}
+
+// the board as an SVG picture, with the lines a solution draws from letter
+// to letter, a color per word. the sides go round a square, or whatever
+// shape has as many sides as the board
+pub fn svg(puzzle: &Puzzle, solution: Option<&Solution>) -> String {
+    const SIZE: f64 = 400.0;
+    const COLORS: [&str; 6] = [
+        "#e8553e", "#3e7de8", "#2e9e4f", "#b04ee0", "#e0a21a", "#1ab0b0",
+    ];
+    let sides = puzzle.letter_groups();
+    let (center, radius) = (SIZE / 2.0, SIZE * 0.3);
+
+    // the corners clockwise from the top left, so a square sits flat
+    let count = sides.len() as f64;
+    let corners: Vec<(f64, f64)> = (0..sides.len())
+        .map(|i| {
+            let angle = (-90.0 - 180.0 / count + 360.0 * i as f64 / count).to_radians();
+            (center + radius * angle.cos(), center + radius * angle.sin())
+        })
+        .collect();
+
+    // each letter spaced out along its side. on a square the bottom and
+    // left sides run the same way as --draw shows them, left to right and
+    // top to bottom
+    let mut positions: HashMap<char, (f64, f64)> = HashMap::new();
+    let mut letters = String::new();
+    for (i, side) in sides.iter().enumerate() {
+        let (mut start, mut end) = (corners[i], corners[(i + 1) % corners.len()]);
+        if sides.len() == 4 && i >= 2 {
+            (start, end) = (end, start);
+        }
+        let middle = (
+            (start.0 + end.0) / 2.0 - center,
+            (start.1 + end.1) / 2.0 - center,
+        );
+        let distance = (middle.0 * middle.0 + middle.1 * middle.1).sqrt().max(1.0);
+        let normal = (middle.0 / distance, middle.1 / distance);
+        for (j, &c) in side.iter().enumerate() {
+            let along = (j + 1) as f64 / (side.len() + 1) as f64;
+            let (x, y) = (
+                start.0 + (end.0 - start.0) * along,
+                start.1 + (end.1 - start.1) * along,
+            );
+            positions.insert(c, (x, y));
+            // the letter goes just outside its side
+            let (lx, ly) = (x + normal.0 * 24.0, y + normal.1 * 24.0);
+            letters.push_str(&format!(
+                "  <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"6\" fill=\"white\" stroke=\"black\" stroke-width=\"2\"/>\n",
+                x, y
+            ));
+            letters.push_str(&format!(
+                "  <text x=\"{:.1}\" y=\"{:.1}\" font-family=\"sans-serif\" font-size=\"22\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text>\n",
+                lx,
+                ly,
+                c.to_uppercase()
+            ));
+        }
+    }
+
+    let mut lines = String::new();
+    for (i, word) in solution
+        .map_or(&[][..], |solution| &solution.words)
+        .iter()
+        .enumerate()
+    {
+        let points: Vec<String> = word
+            .chars()
+            .filter_map(|c| positions.get(&c))
+            .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+            .collect();
+        lines.push_str(&format!(
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"3\" stroke-linejoin=\"round\" opacity=\"0.8\"/>\n",
+            points.join(" "),
+            COLORS[i % COLORS.len()]
+        ));
+    }
+
+    let outline: Vec<String> = corners
+        .iter()
+        .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+        .collect();
+    let title = solution.map_or_else(String::new, |solution| {
+        format!(
+            "  <title>{}</title>\n",
+            solution.words.join(" - ").to_uppercase()
+        )
+    });
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {size} {size}\">\n{}  <rect width=\"{size}\" height=\"{size}\" fill=\"#fbe3d4\"/>\n  <polygon points=\"{}\" fill=\"white\" stroke=\"black\" stroke-width=\"3\"/>\n{}{}</svg>\n",
+        title,
+        outline.join(" "),
+        lines,
+        letters,
+        size = SIZE
+    )
+}
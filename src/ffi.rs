@@ -0,0 +1,180 @@
+// a C interface to the solver, for calling it from other languages (e.g.
+// Swift or Kotlin) through the cdylib. include/lbsolver.h declares it
+//
+// a solver is made with lb_solver_new and freed with lb_solver_free, and
+// each lb_solver_solve hands back a list of solutions the caller owns
+// until it's passed to lb_solution_free. strings are NUL terminated utf-8.
+// a panic is caught before it gets back to C, and reported like an error
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::{LbError, Puzzle, Solution, Solver};
+
+// how lb_solver_solve went, so running out of time or failing can be told
+// apart from a board without any solutions
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LbStatus {
+    // the solutions were found, an empty list if the board has none
+    Ok = 0,
+    // a null solver, or ignore words that aren't utf-8
+    InvalidArgument = 1,
+    // the search ran out of time or nodes before it found any
+    Timeout = 2,
+    // anything else the solver couldn't do
+    Error = 3,
+    // the solver panicked, and shouldn't be used again
+    Panic = 4,
+}
+
+// a chain of words that solves the board
+#[repr(C)]
+pub struct LbSolution {
+    pub words: *mut *mut c_char,
+    pub word_count: usize,
+    pub letter_count: usize,
+}
+
+// the solutions from one call to lb_solver_solve
+#[repr(C)]
+pub struct LbSolutions {
+    pub solutions: *mut LbSolution,
+    pub count: usize,
+}
+
+// the words in a space separated string, an empty list for a null pointer,
+// none if it isn't utf-8
+unsafe fn words(s: *const c_char) -> Option<Vec<String>> {
+    if s.is_null() {
+        return Some(Vec::new());
+    }
+    let s = CStr::from_ptr(s).to_str().ok()?;
+    Some(s.split_whitespace().map(str::to_lowercase).collect())
+}
+
+// hand a boxed slice over to C as a pointer, undone by `from_c_slice`
+fn into_c_slice<T>(items: Vec<T>) -> *mut T {
+    Box::into_raw(items.into_boxed_slice()) as *mut T
+}
+
+unsafe fn from_c_slice<T>(items: *mut T, len: usize) -> Box<[T]> {
+    Box::from_raw(ptr::slice_from_raw_parts_mut(items, len))
+}
+
+impl LbSolution {
+    fn new(solution: Solution) -> LbSolution {
+        let words: Vec<*mut c_char> = solution
+            .words
+            .into_iter()
+            .map(|word| CString::new(word).unwrap_or_default().into_raw())
+            .collect();
+        LbSolution {
+            word_count: words.len(),
+            words: into_c_slice(words),
+            letter_count: solution.letter_count,
+        }
+    }
+}
+
+/// make a solver for a board like "abc def ghi jkl" with the word list at
+/// `dictionary_path`, returning null if the board or the word list is no good
+///
+/// # Safety
+///
+/// both arguments must be NUL terminated strings
+#[no_mangle]
+pub unsafe extern "C" fn lb_solver_new(
+    sides: *const c_char,
+    dictionary_path: *const c_char,
+) -> *mut Solver {
+    if sides.is_null() || dictionary_path.is_null() {
+        return ptr::null_mut();
+    }
+    let (Some(groups), Ok(path)) = (words(sides), CStr::from_ptr(dictionary_path).to_str()) else {
+        return ptr::null_mut();
+    };
+    match panic::catch_unwind(|| Puzzle::new(&groups).and_then(|puzzle| Solver::open(puzzle, path)))
+    {
+        Ok(Ok(solver)) => Box::into_raw(Box::new(solver)),
+        Ok(Err(_)) | Err(_) => ptr::null_mut(),
+    }
+}
+
+/// # Safety
+///
+/// `solver` must be from `lb_solver_new`, or null
+#[no_mangle]
+pub unsafe extern "C" fn lb_solver_set_max_words(solver: *mut Solver, max_words: usize) {
+    if let Some(solver) = solver.as_mut() {
+        solver.set_max_words(max_words);
+    }
+}
+
+/// the best solutions for the board, leaving out the words in the space
+/// separated `ignore_words` (which can be null). null unless `status`, if
+/// it isn't null, is set to `LbStatus::Ok`
+///
+/// # Safety
+///
+/// `solver` must be from `lb_solver_new`, `ignore_words` a NUL terminated
+/// string or null, and `status` null or somewhere to write the status
+#[no_mangle]
+pub unsafe extern "C" fn lb_solver_solve(
+    solver: *mut Solver,
+    ignore_words: *const c_char,
+    status: *mut LbStatus,
+) -> *mut LbSolutions {
+    let (solutions, solved) = match solve(solver, ignore_words) {
+        Ok(solutions) => (Box::into_raw(Box::new(solutions)), LbStatus::Ok),
+        Err(err) => (ptr::null_mut(), err),
+    };
+    if let Some(status) = status.as_mut() {
+        *status = solved;
+    }
+    solutions
+}
+
+unsafe fn solve(solver: *mut Solver, ignore_words: *const c_char) -> Result<LbSolutions, LbStatus> {
+    let (Some(solver), Some(ignore_words)) = (solver.as_mut(), words(ignore_words)) else {
+        return Err(LbStatus::InvalidArgument);
+    };
+    let found = panic::catch_unwind(AssertUnwindSafe(|| solver.run_solver(&ignore_words)))
+        .map_err(|_| LbStatus::Panic)?;
+    let solutions: Vec<LbSolution> = match found {
+        Ok(solutions) => solutions.into_iter().map(LbSolution::new).collect(),
+        Err(LbError::Timeout) => return Err(LbStatus::Timeout),
+        Err(_) => return Err(LbStatus::Error),
+    };
+    Ok(LbSolutions {
+        count: solutions.len(),
+        solutions: into_c_slice(solutions),
+    })
+}
+
+/// # Safety
+///
+/// `solutions` must be from `lb_solver_solve`, or null, and is only freed once
+#[no_mangle]
+pub unsafe extern "C" fn lb_solution_free(solutions: *mut LbSolutions) {
+    if solutions.is_null() {
+        return;
+    }
+    let solutions = Box::from_raw(solutions);
+    for solution in from_c_slice(solutions.solutions, solutions.count).iter() {
+        for &word in from_c_slice(solution.words, solution.word_count).iter() {
+            drop(CString::from_raw(word));
+        }
+    }
+}
+
+/// # Safety
+///
+/// `solver` must be from `lb_solver_new`, or null, and is only freed once
+#[no_mangle]
+pub unsafe extern "C" fn lb_solver_free(solver: *mut Solver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
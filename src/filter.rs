@@ -0,0 +1,186 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::{compiled, log, CompiledDictionary, Frequencies, LbError};
+
+// something that decides which words to keep as a word list is loaded,
+// e.g. only words of a certain length, or no rude words. words are checked
+// lowercased, the way the solver sees them. any `Fn(&str) -> bool` is one,
+// so a filter can be a closure, e.g. one that matches a regex
+pub trait WordFilter {
+    fn accept(&self, word: &str) -> bool;
+}
+
+impl<F: Fn(&str) -> bool> WordFilter for F {
+    fn accept(&self, word: &str) -> bool {
+        self(word)
+    }
+}
+
+// several filters stacked up, a word is kept if every one of them keeps it
+#[derive(Default)]
+pub struct Filters<'a> {
+    filters: Vec<Box<dyn WordFilter + Send + Sync + 'a>>,
+}
+
+impl<'a> Filters<'a> {
+    pub fn new() -> Filters<'a> {
+        Filters::default()
+    }
+
+    pub fn with<F: WordFilter + Send + Sync + 'a>(mut self, filter: F) -> Filters<'a> {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    pub fn push<F: WordFilter + Send + Sync + 'a>(&mut self, filter: F) {
+        self.filters.push(Box::new(filter));
+    }
+
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+}
+
+impl WordFilter for Filters<'_> {
+    fn accept(&self, word: &str) -> bool {
+        self.filters.iter().all(|filter| filter.accept(word))
+    }
+}
+
+// words with at least min letters, and at most max if there is one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Length {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl WordFilter for Length {
+    fn accept(&self, word: &str) -> bool {
+        let length = word.chars().count();
+        length >= self.min && self.max.is_none_or(|max| length <= max)
+    }
+}
+
+// words among the max_rank most common in a frequency list
+#[derive(Debug, Clone, Copy)]
+pub struct Common<'a> {
+    pub frequencies: &'a Frequencies,
+    pub max_rank: usize,
+}
+
+impl WordFilter for Common<'_> {
+    fn accept(&self, word: &str) -> bool {
+        self.frequencies.is_common(word, self.max_rank)
+    }
+}
+
+// words not in a list, e.g. of rude words
+#[derive(Debug, Clone, Default)]
+pub struct Blocked(pub HashSet<String>);
+
+impl WordFilter for Blocked {
+    fn accept(&self, word: &str) -> bool {
+        !self.0.contains(word)
+    }
+}
+
+// kinds of word to leave out of a word list as it's read, so the solutions
+// lean towards words people would actually play. proper nouns and
+// abbreviations are spotted by how they're written, so they're dropped
+// before the words are lowercased
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DictionaryFilter {
+    // words that are another word in the list with an s or es on the
+    // end, or ies in place of a y
+    pub plurals: bool,
+    // entries that start with a capital letter, e.g. "Paris"
+    pub proper_nouns: bool,
+    // entries with a full stop in them, e.g. "etc.", or written all in
+    // capitals, e.g. "NASA"
+    pub abbreviations: bool,
+}
+
+impl DictionaryFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == DictionaryFilter::default()
+    }
+
+    // whether an entry in the word list, as it's written there, is kept
+    pub fn keeps_entry(&self, entry: &str) -> bool {
+        if self.abbreviations && (entry.contains('.') || is_all_capitals(entry)) {
+            return false;
+        }
+        let first_letter = entry.chars().find(|c| c.is_alphabetic());
+        !(self.proper_nouns && first_letter.is_some_and(char::is_uppercase))
+    }
+
+    // the words from the source that the filter keeps, lowercased the same
+    // as `read_words`
+    pub fn read_words<R: Read>(&self, source: R) -> Result<Vec<String>, LbError> {
+        let entries = compiled::read_entries(source)?;
+        Ok(self.filter(entries.iter().map(String::as_str)))
+    }
+
+    fn filter<'a, I: Iterator<Item = &'a str>>(&self, entries: I) -> Vec<String> {
+        let mut words: Vec<String> = entries
+            .filter(|entry| self.keeps_entry(entry))
+            .map(str::to_lowercase)
+            .collect();
+        if self.plurals {
+            remove_plurals(&mut words);
+        }
+        words
+    }
+
+    // the same as `read_word_list`, with the filter applied
+    pub fn read_word_list<P: AsRef<Path>>(&self, path: P) -> Result<Vec<String>, LbError> {
+        let path = path.as_ref();
+        let _span = log::Span::new(log::INFO, format_args!("reading {}", path.display()));
+        // a compiled list is mapped, so only the words kept are copied
+        let words = if CompiledDictionary::is_compiled_file(path) {
+            self.filter(CompiledDictionary::open(path)?.words())
+        } else {
+            self.read_words(File::open(path)?)?
+        };
+        log::debug!("{} words kept from {}", words.len(), path.display());
+        Ok(words)
+    }
+}
+
+// more than one letter, and every one of them a capital
+fn is_all_capitals(entry: &str) -> bool {
+    let letters: Vec<char> = entry.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.len() > 1 && letters.iter().all(|c| c.is_uppercase())
+}
+
+// drop the words that look like the plural of another word in the list.
+// words ending in ss, us or is are left alone, e.g. "glass", "bonus" and
+// "axis", since taking the s off those rarely leaves the singular
+fn remove_plurals(words: &mut Vec<String>) {
+    let known: HashSet<String> = words.iter().cloned().collect();
+    words.retain(|word| !is_plural(word, &known));
+}
+
+fn is_plural(word: &str, known: &HashSet<String>) -> bool {
+    if !word.ends_with('s') || ["ss", "us", "is"].iter().any(|end| word.ends_with(end)) {
+        return false;
+    }
+    let stem = &word[..word.len() - 1];
+    if known.contains(stem) {
+        return true;
+    }
+    if let Some(stem) = word.strip_suffix("es") {
+        if known.contains(stem) {
+            return true;
+        }
+    }
+    word.strip_suffix("ies")
+        .is_some_and(|stem| known.contains(&format!("{}y", stem)))
+}
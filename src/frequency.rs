@@ -0,0 +1,80 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+use crate::{LbError, Solution};
+
+// how common each word is, as a rank where 1 is the most common word
+//
+// the file has one word per line, either on its own (so the line number is
+// its rank) or followed by its rank, e.g. "the 1"
+#[derive(Debug, Default, Clone)]
+pub struct Frequencies {
+    ranks: HashMap<String, usize>,
+}
+
+impl Frequencies {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Frequencies, LbError> {
+        let source = File::open(path)?;
+        Frequencies::from_reader(io::BufReader::new(source))
+    }
+
+    pub fn from_reader<R: BufRead>(source: R) -> Result<Frequencies, LbError> {
+        let mut ranks = HashMap::new();
+        for (line_number, line) in source.lines().enumerate() {
+            let line = line?;
+            let mut fields = line.split_whitespace();
+            let word = match fields.next() {
+                Some(word) => word,
+                None => continue,
+            };
+            let rank = fields
+                .next()
+                .and_then(|rank| rank.parse().ok())
+                .unwrap_or(line_number + 1);
+            // if a word is listed twice keep its most common rank
+            let entry = ranks.entry(word.to_string()).or_insert(rank);
+            *entry = (*entry).min(rank);
+        }
+        Ok(Frequencies { ranks })
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranks.is_empty()
+    }
+
+    pub fn rank(&self, word: &str) -> Option<usize> {
+        self.ranks.get(word).copied()
+    }
+
+    // whether the word is one of the max_rank most common, words missing
+    // from the list never are
+    pub fn is_common(&self, word: &str, max_rank: usize) -> bool {
+        self.rank(word).is_some_and(|rank| rank <= max_rank)
+    }
+
+    // the rank of the least common word in the solution, words missing from
+    // the list are treated as rarer than anything in it
+    pub fn rarity(&self, solution: &Solution) -> usize {
+        solution
+            .words
+            .iter()
+            .map(|word| self.rank(word).unwrap_or(usize::MAX))
+            .max()
+            .unwrap_or(0)
+    }
+
+    // the solution whose least common word is more common first, then the
+    // in-game ranking
+    pub fn cmp_by_rarity(&self, solution: &Solution, other: &Solution) -> Ordering {
+        self.rarity(solution)
+            .cmp(&self.rarity(other))
+            .then_with(|| solution.cmp_by_letters(other))
+    }
+}
@@ -0,0 +1,273 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+// a word list kept as a minimal automaton instead of a string per word:
+// words that start the same share the path for their start, and words
+// that end the same share the path for their end, so a big list takes a
+// fraction of the memory and can be searched by prefix or pattern without
+// looking at every word. the same idea as the fst crate's `Set`, built
+// here so the library doesn't need it
+//
+// each state's edges are kept together, sorted by byte, so the words come
+// back out in byte order
+#[derive(Debug, Clone, Default)]
+pub struct WordSet {
+    // where each state's edges start in `edges`, and one more for the end
+    // of the last state's. state 0 is where every word starts
+    starts: Vec<u32>,
+    // whether a word ends at each state
+    finals: Vec<bool>,
+    edges: Vec<(u8, u32)>,
+    len: usize,
+}
+
+// a state while the set is being built, before it's known to be the same
+// as one that's already there
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct Draft {
+    is_final: bool,
+    edges: Vec<(u8, usize)>,
+}
+
+impl WordSet {
+    // the words can be in any order, and repeats are dropped
+    pub fn from_words<I, S>(words: I) -> WordSet
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut words: Vec<String> = words
+            .into_iter()
+            .map(|word| word.as_ref().to_string())
+            .collect();
+        words.sort_unstable();
+        words.dedup();
+
+        // each word only adds the states after the part it shares with
+        // the one before it. once a word has moved past a state, nothing
+        // after it can change that state, so it's swapped for an equal one
+        // that's already been kept, if there is one (Daciuk et al.'s
+        // incremental construction from sorted words)
+        let mut drafts = vec![Draft::default()];
+        let mut kept: HashMap<Draft, usize> = HashMap::new();
+        // the states of the last word, after the first, that haven't been
+        // checked against the kept ones yet
+        let mut unchecked: Vec<usize> = Vec::new();
+        let mut previous = "";
+        for word in &words {
+            let shared = word
+                .bytes()
+                .zip(previous.bytes())
+                .take_while(|(a, b)| a == b)
+                .count();
+            replace_or_keep(&mut drafts, &mut kept, &mut unchecked, shared);
+            let mut state = unchecked.last().copied().unwrap_or(0);
+            for &byte in &word.as_bytes()[shared..] {
+                drafts.push(Draft::default());
+                let next = drafts.len() - 1;
+                drafts[state].edges.push((byte, next));
+                unchecked.push(next);
+                state = next;
+            }
+            drafts[state].is_final = true;
+            previous = word;
+        }
+        replace_or_keep(&mut drafts, &mut kept, &mut unchecked, 0);
+
+        // lay out the states that are still reachable, one after another
+        let mut numbers: HashMap<usize, u32> = HashMap::from([(0, 0)]);
+        let mut order = vec![0];
+        let mut i = 0;
+        while i < order.len() {
+            for &(_, next) in &drafts[order[i]].edges {
+                if let Entry::Vacant(entry) = numbers.entry(next) {
+                    entry.insert(order.len() as u32);
+                    order.push(next);
+                }
+            }
+            i += 1;
+        }
+        let mut set = WordSet {
+            starts: Vec::with_capacity(order.len() + 1),
+            finals: Vec::with_capacity(order.len()),
+            edges: Vec::new(),
+            len: words.len(),
+        };
+        for &state in &order {
+            set.starts.push(set.edges.len() as u32);
+            set.finals.push(drafts[state].is_final);
+            set.edges.extend(
+                drafts[state]
+                    .edges
+                    .iter()
+                    .map(|&(byte, next)| (byte, numbers[&next])),
+            );
+        }
+        set.starts.push(set.edges.len() as u32);
+        set
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // how many states and edges the automaton has, a rough measure of the
+    // memory it takes
+    pub fn size(&self) -> (usize, usize) {
+        (self.finals.len(), self.edges.len())
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.walk(0, word.as_bytes())
+            .is_some_and(|state| self.finals[state])
+    }
+
+    // every word, in byte order
+    pub fn words(&self) -> Vec<String> {
+        self.starting_with("")
+    }
+
+    // the words that start with the prefix, in byte order
+    pub fn starting_with(&self, prefix: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        if let Some(state) = self.walk(0, prefix.as_bytes()) {
+            self.collect(state, &mut prefix.as_bytes().to_vec(), &mut words);
+        }
+        words
+    }
+
+    // the words matching a pattern of letters and . for any one letter,
+    // e.g. "l..k" for look and lock, in byte order
+    pub fn matching(&self, pattern: &str) -> Vec<String> {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let mut words = Vec::new();
+        if !self.finals.is_empty() {
+            self.collect_matching(0, &pattern, &mut Vec::new(), &mut words);
+        }
+        words
+    }
+
+    fn edges(&self, state: usize) -> &[(u8, u32)] {
+        &self.edges[self.starts[state] as usize..self.starts[state + 1] as usize]
+    }
+
+    fn next(&self, state: usize, byte: u8) -> Option<usize> {
+        let edges = self.edges(state);
+        edges
+            .binary_search_by_key(&byte, |&(b, _)| b)
+            .ok()
+            .map(|i| edges[i].1 as usize)
+    }
+
+    // the state reached by following the bytes from a state, if there's a
+    // word that goes that way
+    fn walk(&self, state: usize, bytes: &[u8]) -> Option<usize> {
+        if self.finals.is_empty() {
+            return None;
+        }
+        bytes
+            .iter()
+            .try_fold(state, |state, &byte| self.next(state, byte))
+    }
+
+    fn collect(&self, state: usize, word: &mut Vec<u8>, words: &mut Vec<String>) {
+        if self.finals[state] {
+            words.push(String::from_utf8_lossy(word).into_owned());
+        }
+        for &(byte, next) in self.edges(state) {
+            word.push(byte);
+            self.collect(next as usize, word, words);
+            word.pop();
+        }
+    }
+
+    fn collect_matching(
+        &self,
+        state: usize,
+        pattern: &[char],
+        word: &mut Vec<u8>,
+        words: &mut Vec<String>,
+    ) {
+        let Some((&c, rest)) = pattern.split_first() else {
+            if self.finals[state] {
+                words.push(String::from_utf8_lossy(word).into_owned());
+            }
+            return;
+        };
+        if c != '.' {
+            let mut buffer = [0; 4];
+            let bytes = c.encode_utf8(&mut buffer).as_bytes();
+            if let Some(next) = self.walk(state, bytes) {
+                word.extend_from_slice(bytes);
+                self.collect_matching(next, rest, word, words);
+                word.truncate(word.len() - bytes.len());
+            }
+            return;
+        }
+        // any one letter, which can be more than one byte
+        for &(byte, next) in self.edges(state) {
+            word.push(byte);
+            self.collect_letter(next as usize, utf8_length(byte) - 1, rest, word, words);
+            word.pop();
+        }
+    }
+
+    // follow the rest of a letter's bytes, then carry on matching
+    fn collect_letter(
+        &self,
+        state: usize,
+        remaining: usize,
+        pattern: &[char],
+        word: &mut Vec<u8>,
+        words: &mut Vec<String>,
+    ) {
+        if remaining == 0 {
+            self.collect_matching(state, pattern, word, words);
+            return;
+        }
+        for &(byte, next) in self.edges(state) {
+            word.push(byte);
+            self.collect_letter(next as usize, remaining - 1, pattern, word, words);
+            word.pop();
+        }
+    }
+}
+
+// swap each unchecked state, past the first `down_to`, for an equal one
+// that's already been kept, or keep it. the last ones are done first, so
+// a state's edges only ever point at kept states by the time it's checked
+fn replace_or_keep(
+    drafts: &mut [Draft],
+    kept: &mut HashMap<Draft, usize>,
+    unchecked: &mut Vec<usize>,
+    down_to: usize,
+) {
+    while unchecked.len() > down_to {
+        let state = unchecked.pop().unwrap_or_default();
+        let parent = unchecked.last().copied().unwrap_or(0);
+        match kept.get(&drafts[state]) {
+            Some(&same) => {
+                if let Some(edge) = drafts[parent].edges.last_mut() {
+                    edge.1 = same;
+                }
+            }
+            None => {
+                kept.insert(drafts[state].clone(), state);
+            }
+        }
+    }
+}
+
+// how many bytes the UTF-8 letter starting with this byte takes
+fn utf8_length(first: u8) -> usize {
+    match first {
+        0xf0.. => 4,
+        0xe0.. => 3,
+        0xc0.. => 2,
+        _ => 1,
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{Puzzle, RuleCheck, Solution};
+
+// makes random 4 x 3 boards that can be solved in two words. it works
+// backwards from the answer: pick two words that chain together and use
+// exactly 12 letters between them, then share those letters out between
+// the sides so no two letters next to each other in either word end up
+// on the same side
+
+const SIDES: usize = 4;
+const LETTERS_PER_SIDE: usize = 3;
+const LETTERS: usize = SIDES * LETTERS_PER_SIDE;
+
+// how many first words to try before giving up on a word list
+const ATTEMPTS: usize = 10_000;
+
+// a small, fast random number generator (splitmix64), so boards can be
+// made again from the same seed
+#[derive(Debug, Clone)]
+pub struct Random(u64);
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    // a number from 0 up to but not including n
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+// the letter's bit, for words made of a to z
+fn bit(c: u8) -> u32 {
+    1 << (c - b'a')
+}
+
+#[derive(Debug)]
+pub struct Generator {
+    // the words that could be half of a solution
+    words: Vec<String>,
+    // the letters each word uses, a bit per letter
+    masks: Vec<u32>,
+    // the indexes of the words starting with each letter
+    by_first_letter: Vec<Vec<usize>>,
+}
+
+impl Generator {
+    pub fn new<S: AsRef<str>>(words: &[S]) -> Generator {
+        // only plain a to z words can go on a board, and a word with a
+        // letter twice in a row never fits one
+        let words: Vec<String> = words
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|word| word.len() >= 3 && word.bytes().all(|c| c.is_ascii_lowercase()))
+            .filter(|word| word.as_bytes().windows(2).all(|pair| pair[0] != pair[1]))
+            .filter(|word| {
+                word.bytes().fold(0, |mask, c| mask | bit(c)).count_ones() as usize <= LETTERS
+            })
+            .map(str::to_string)
+            .collect();
+        let masks = words
+            .iter()
+            .map(|word| word.bytes().fold(0, |mask, c| mask | bit(c)))
+            .collect();
+        let mut by_first_letter = vec![Vec::new(); 26];
+        for (i, word) in words.iter().enumerate() {
+            by_first_letter[(word.as_bytes()[0] - b'a') as usize].push(i);
+        }
+        Generator {
+            words,
+            masks,
+            by_first_letter,
+        }
+    }
+
+    // a random board, and the two word solution it was made from. none if
+    // the word list doesn't seem to have any pairs that work
+    pub fn generate(&self, random: &mut Random) -> Option<(Vec<String>, Solution)> {
+        if self.words.is_empty() {
+            return None;
+        }
+        for _ in 0..ATTEMPTS {
+            let first = random.below(self.words.len());
+            let last_letter = *self.words[first].as_bytes().last()?;
+            let candidates = &self.by_first_letter[(last_letter - b'a') as usize];
+            if candidates.is_empty() {
+                continue;
+            }
+
+            // look through the words that chain on from a random place
+            let offset = random.below(candidates.len());
+            for n in 0..candidates.len() {
+                let second = candidates[(offset + n) % candidates.len()];
+                let mask = self.masks[first] | self.masks[second];
+                if second == first || mask.count_ones() as usize != LETTERS {
+                    continue;
+                }
+                let solution = [&self.words[first], &self.words[second]];
+                if let Some(sides) = assign_sides(&solution, random) {
+                    let words: Vec<String> = solution.iter().map(|word| word.to_string()).collect();
+                    // make sure the board really is solved by the words
+                    let solves = Puzzle::new(&sides).is_ok_and(|puzzle| {
+                        puzzle.check(&words, |_| true).iter().all(RuleCheck::passed)
+                    });
+                    if solves {
+                        return Some((sides, Solution::new(words)));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+// share the letters of the words out between the sides, so that letters
+// next to each other in a word are always on different sides
+fn assign_sides(words: &[&String], random: &mut Random) -> Option<Vec<String>> {
+    // the letters each letter is next to somewhere in the words
+    let mut neighbours = [0u32; 26];
+    let mut letters: Vec<u8> = Vec::new();
+    for word in words {
+        for pair in word.as_bytes().windows(2) {
+            neighbours[(pair[0] - b'a') as usize] |= bit(pair[1]);
+            neighbours[(pair[1] - b'a') as usize] |= bit(pair[0]);
+        }
+        letters.extend(word.bytes());
+    }
+    letters.sort();
+    letters.dedup();
+    random.shuffle(&mut letters);
+
+    let mut sides: Vec<Vec<u8>> = vec![Vec::new(); SIDES];
+    if !place(&letters, &neighbours, &mut sides) {
+        return None;
+    }
+    random.shuffle(&mut sides);
+    Some(
+        sides
+            .into_iter()
+            .map(|mut side| {
+                side.sort();
+                String::from_utf8(side).unwrap_or_default()
+            })
+            .collect(),
+    )
+}
+
+// put the first letter on a side with room that has none of its
+// neighbours, then the rest, backing up when a letter won't go anywhere
+fn place(letters: &[u8], neighbours: &[u32; 26], sides: &mut [Vec<u8>]) -> bool {
+    let (&letter, rest) = match letters.split_first() {
+        Some(split) => split,
+        None => return true,
+    };
+    for i in 0..sides.len() {
+        let fits = sides[i].len() < LETTERS_PER_SIDE
+            && sides[i]
+                .iter()
+                .all(|&other| neighbours[(letter - b'a') as usize] & bit(other) == 0);
+        if fits {
+            sides[i].push(letter);
+            if place(rest, neighbours, sides) {
+                return true;
+            }
+            sides[i].pop();
+        }
+    }
+    false
+}
@@ -0,0 +1,111 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::read_ignore_file;
+
+// the words the user has said they never want to see, kept between runs
+// and ignored in every solve, e.g. words the NYT game doesn't accept.
+// they're kept in $XDG_DATA_HOME/lbsolver/ignored.txt (or
+// ~/.local/share/lbsolver/ignored.txt), one word per line
+pub enum IgnoreEdit {
+    Add(Vec<String>),
+    Remove(Vec<String>),
+    List,
+}
+
+impl IgnoreEdit {
+    pub fn parse(mut words: Vec<String>) -> Result<IgnoreEdit, String> {
+        if words.is_empty() {
+            return Err("ignore needs add, remove or list".to_string());
+        }
+        let action = words.remove(0);
+        let words: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+        match action.as_str() {
+            "add" | "remove" if words.is_empty() => {
+                Err(format!("ignore {} needs some words", action))
+            }
+            "add" => Ok(IgnoreEdit::Add(words)),
+            "remove" => Ok(IgnoreEdit::Remove(words)),
+            "list" => Ok(IgnoreEdit::List),
+            _ => Err(format!(
+                "unknown ignore action {}, expected add, remove or list",
+                action
+            )),
+        }
+    }
+}
+
+// the other saved word lists in the same place, words to add to the word
+// lists and words to take out of them
+pub const WHITELIST: &str = "whitelist.txt";
+pub const BLACKLIST: &str = "blacklist.txt";
+const IGNORED: &str = "ignored.txt";
+
+fn path(name: &str) -> Option<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))?;
+    Some(base.join("lbsolver").join(name))
+}
+
+// the saved ignore words, none if nothing's been saved yet
+pub fn load() -> Result<Vec<String>, String> {
+    load_saved(IGNORED)
+}
+
+// the words saved in one of the lists, none if it hasn't been made
+pub fn load_saved(name: &str) -> Result<Vec<String>, String> {
+    match path(name) {
+        Some(path) if path.is_file() => read_ignore_file(&path),
+        _ => Ok(Vec::new()),
+    }
+}
+
+// change the saved words and say what changed
+pub fn run(edit: &IgnoreEdit) -> Result<(), String> {
+    let path =
+        path(IGNORED).ok_or("could not find a home directory to keep the ignored words in")?;
+    let mut words = load()?;
+    match edit {
+        IgnoreEdit::List => {
+            for word in &words {
+                println!("{}", word);
+            }
+            return Ok(());
+        }
+        IgnoreEdit::Add(added) => {
+            for word in added {
+                if words.contains(word) {
+                    println!("{} is already ignored", word);
+                } else {
+                    println!("Ignoring {}", word);
+                    words.push(word.clone());
+                }
+            }
+        }
+        IgnoreEdit::Remove(removed) => {
+            for word in removed {
+                if words.contains(word) {
+                    println!("No longer ignoring {}", word);
+                } else {
+                    println!("{} wasn't ignored", word);
+                }
+            }
+            words.retain(|word| !removed.contains(word));
+        }
+    }
+    words.sort();
+    save(&path, &words).map_err(|err| format!("could not write {} ({})", path.display(), err))
+}
+
+fn save(path: &Path, words: &[String]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut contents = words.join("\n");
+    contents.push('\n');
+    fs::write(path, contents)
+}
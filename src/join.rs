@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+
+use crate::{log, Solution, Solver};
+
+// solutions of two and three words found straight from the word indexes
+// rather than by the search. a three word chain is found from its middle
+// word: the words that end with its first letter go before it, from the
+// end letter dictionary, and the words that start with its last letter go
+// after it, from the start letter dictionary. the two sides meet on the
+// letters the middle word leaves uncovered
+//
+// to check a side quickly, the word indexes keep a `CoveringWords` table,
+// so one lookup says whether any word covers all the letters still
+// missing. that takes an entry per set of letters, so bigger boards are
+// left to the search
+const MAX_LETTERS: u32 = 16;
+
+impl Solver {
+    // the solutions with the fewest words when that's three or fewer, up to
+    // the solution limit. none when there aren't any that short, or the
+    // board is too big to join words on
+    pub(crate) fn join_short_solutions(&self, ignore_words: &[String]) -> Option<Vec<Solution>> {
+        let full_mask = self.puzzle.full_mask();
+        let covering = self.words.covering.as_ref()?;
+        if self.words.dictionary.is_empty() {
+            return None;
+        }
+        let _span = log::Span::new(log::INFO, format_args!("joining words for short chains"));
+
+        // a required word that isn't on the board rules out every solution
+        let words = match self.join_words(ignore_words) {
+            Some(words) => words,
+            None => return Some(Vec::new()),
+        };
+        let JoinWords {
+            usable,
+            is_first,
+            required,
+        } = &words;
+        let has_required = |chain: &[usize]| {
+            required
+                .iter()
+                .all(|&r| chain.iter().any(|&i| self.is_same_word(i, r)))
+        };
+
+        let mut found = Vec::new();
+        let max_words = self.max_words.min(3);
+
+        // a single word, which needs nothing joining
+        for i in (0..self.words.dictionary.len()).filter(|&i| is_first[i]) {
+            if self.words.word_masks[i] == full_mask && has_required(&[i]) {
+                found.push(self.chain_solution(&[i]));
+            }
+        }
+        if !found.is_empty() {
+            return Some(self.limit_found(found));
+        }
+        if max_words < 2 {
+            return None;
+        }
+
+        // two words
+        let pairs = self.join_pairs(&words, self.search_limit(2));
+        if !pairs.is_empty() || max_words < 3 {
+            let found: Vec<Solution> = pairs.iter().map(|pair| self.chain_solution(pair)).collect();
+            return (!found.is_empty()).then_some(found);
+        }
+
+        let limit = self.search_limit(3);
+
+        // three words, from the middle one out
+        for middle in (0..self.words.dictionary.len()).filter(|&i| usable[i]) {
+            let (first_letter, last) = (self.first_letter(middle), self.last_letter(middle));
+            let befores = match self.words.end_letter_dictionary.get(&first_letter) {
+                Some(befores) => befores,
+                None => continue,
+            };
+            for &before in befores {
+                if self.is_same_word(before, middle)
+                    || !is_first[before]
+                    || !self.allows([before], middle)
+                {
+                    continue;
+                }
+                let missing =
+                    full_mask & !(self.words.word_masks[before] | self.words.word_masks[middle]);
+                if !covering.any(last, missing) {
+                    continue;
+                }
+                for &after in self
+                    .words
+                    .start_letter_dictionary
+                    .get(&last)
+                    .into_iter()
+                    .flatten()
+                {
+                    let chain = [before, middle, after];
+                    if !self.is_same_word(after, before)
+                        && !self.is_same_word(after, middle)
+                        && usable[after]
+                        && self.words.word_masks[after] & missing == missing
+                        && has_required(&chain)
+                        && self.allows([before, middle], after)
+                    {
+                        found.push(self.chain_solution(&chain));
+                        if found.len() >= limit {
+                            return Some(found);
+                        }
+                    }
+                }
+            }
+        }
+        (!found.is_empty()).then_some(found)
+    }
+
+    // which words can be used where, none if a required word can't be
+    // played on the board at all
+    pub(crate) fn join_words(&self, ignore_words: &[String]) -> Option<JoinWords> {
+        let usable: Vec<bool> = self
+            .words
+            .dictionary
+            .iter()
+            .map(|word| !ignore_words.contains(word))
+            .collect();
+        let mut is_first = vec![false; self.words.dictionary.len()];
+        for i in self.first_words() {
+            is_first[i] = usable[i] && self.allows([], i);
+        }
+        let required = self
+            .required_words
+            .iter()
+            .map(|word| self.words.dictionary.iter().position(|w| w == word))
+            .collect::<Option<Vec<usize>>>()?;
+        Some(JoinWords {
+            usable,
+            is_first,
+            required,
+        })
+    }
+
+    // the pairs of words that chain together and cover the board between
+    // them, up to the limit. the words are grouped by the letter they link
+    // on and the board letters they cover, and groups are only paired up
+    // when their letters add up to the whole board. there are far fewer
+    // groups than words, so most words are never looked at in pairs, and
+    // a first word no word at all can finish is left out from the start
+    pub(crate) fn join_pairs(&self, words: &JoinWords, limit: usize) -> Vec<[usize; 2]> {
+        let full_mask = self.puzzle.full_mask();
+        let mut ends: BTreeMap<char, BTreeMap<u64, Vec<usize>>> = BTreeMap::new();
+        let mut starts: BTreeMap<char, BTreeMap<u64, Vec<usize>>> = BTreeMap::new();
+        for (i, &mask) in self.words.word_masks.iter().enumerate() {
+            if words.is_first[i] && self.can_finish(i, full_mask & !mask, 1) {
+                let groups = ends.entry(self.last_letter(i)).or_default();
+                groups.entry(mask).or_default().push(i);
+            }
+            if words.usable[i] {
+                let groups = starts.entry(self.first_letter(i)).or_default();
+                groups.entry(mask).or_default().push(i);
+            }
+        }
+
+        let mut pairs = Vec::new();
+        for (letter, end_groups) in &ends {
+            let start_groups = match starts.get(letter) {
+                Some(start_groups) => start_groups,
+                None => continue,
+            };
+            for (&end_mask, firsts) in end_groups {
+                for (&start_mask, seconds) in start_groups {
+                    if end_mask | start_mask != full_mask {
+                        continue;
+                    }
+                    for &first in firsts {
+                        for &second in seconds {
+                            let pair = [first, second];
+                            let has_required = words
+                                .required
+                                .iter()
+                                .all(|&r| pair.iter().any(|&i| self.is_same_word(i, r)));
+                            if self.is_same_word(first, second)
+                                || !has_required
+                                || !self.allows([first], second)
+                            {
+                                continue;
+                            }
+                            pairs.push(pair);
+                            if pairs.len() >= limit {
+                                return pairs;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        pairs
+    }
+
+    fn first_letter(&self, i: usize) -> char {
+        self.words.word_ends[i].0
+    }
+
+    fn last_letter(&self, i: usize) -> char {
+        self.words.word_ends[i].1
+    }
+
+    pub(crate) fn chain_solution(&self, chain: &[usize]) -> Solution {
+        Solution::new(
+            chain
+                .iter()
+                .map(|&i| self.words.dictionary[i].clone())
+                .collect(),
+        )
+    }
+
+    fn limit_found(&self, mut found: Vec<Solution>) -> Vec<Solution> {
+        if let Some(solution) = found.first() {
+            found.truncate(self.search_limit(solution.word_count()));
+        }
+        found
+    }
+}
+
+// the words a join can use, by their index in the dictionary
+pub(crate) struct JoinWords {
+    // whether each word isn't one of the ignored words
+    pub usable: Vec<bool>,
+    // whether each word can start a chain, and the validator lets it
+    pub is_first: Vec<bool>,
+    pub required: Vec<usize>,
+}
+
+// for each letter, whether any word starting with it covers each set of
+// board letters, marking a word's letters and every set they include, and
+// the same for any two words in a row. it's built once with the word
+// indexes, for boards of up to MAX_LETTERS letters, and answers whether a
+// chain ending in a letter could be finished with one or two more words in
+// a single lookup
+#[derive(Debug)]
+pub(crate) struct CoveringWords {
+    covers: BTreeMap<char, Vec<bool>>,
+    pair_covers: BTreeMap<char, Vec<bool>>,
+}
+
+impl CoveringWords {
+    pub(crate) fn new(
+        word_masks: &[u64],
+        word_ends: &[(char, char)],
+        start_letter_dictionary: &BTreeMap<char, Vec<usize>>,
+        end_letter_dictionary: &BTreeMap<char, Vec<usize>>,
+        letters: u32,
+    ) -> Option<CoveringWords> {
+        if letters > MAX_LETTERS {
+            return None;
+        }
+        let size = 1usize << letters;
+        let covers = start_letter_dictionary
+            .iter()
+            .map(|(&letter, words)| {
+                let mut covers = vec![false; size];
+                for &i in words {
+                    covers[word_masks[i] as usize] = true;
+                }
+                include_subsets(&mut covers, letters);
+                (letter, covers)
+            })
+            .collect();
+
+        // two words meet on a letter, so the pairs are found from the
+        // letter they meet on: the words ending in it, from the end letter
+        // dictionary, followed by the words starting with it. each pair is
+        // marked for the first word's start letter
+        let mut pair_covers: BTreeMap<char, Vec<bool>> = BTreeMap::new();
+        for (letter, befores) in end_letter_dictionary {
+            let mut afters: Vec<u64> = start_letter_dictionary
+                .get(letter)
+                .into_iter()
+                .flatten()
+                .map(|&i| word_masks[i])
+                .collect();
+            afters.sort_unstable();
+            afters.dedup();
+            for &before in befores {
+                let covers = pair_covers
+                    .entry(word_ends[before].0)
+                    .or_insert_with(|| vec![false; size]);
+                for &after in &afters {
+                    covers[(word_masks[before] | after) as usize] = true;
+                }
+            }
+        }
+        for covers in pair_covers.values_mut() {
+            include_subsets(covers, letters);
+        }
+        Some(CoveringWords {
+            covers,
+            pair_covers,
+        })
+    }
+
+    // whether any word starting with the letter covers all of the missing
+    // letters. it may turn out to be a word that's already in the chain,
+    // or one that's ignored
+    pub(crate) fn any(&self, letter: char, missing: u64) -> bool {
+        self.covers
+            .get(&letter)
+            .is_some_and(|covers| covers[missing as usize])
+    }
+
+    // the same for two words in a row, the first starting with the letter
+    pub(crate) fn any_pair(&self, letter: char, missing: u64) -> bool {
+        self.pair_covers
+            .get(&letter)
+            .is_some_and(|covers| covers[missing as usize])
+    }
+}
+
+// mark every set a marked set includes, one letter at a time
+fn include_subsets(covers: &mut [bool], letters: u32) {
+    for bit in 0..letters {
+        let bit = 1 << bit;
+        for mask in 0..covers.len() {
+            if mask & bit == 0 {
+                covers[mask] |= covers[mask | bit];
+            }
+        }
+    }
+}
@@ -0,0 +1,2873 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, Read};
+use std::mem;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub mod bee;
+pub mod cache;
+mod checkpoint;
+pub mod compiled;
+pub mod definitions;
+pub mod dictionary;
+pub mod ffi;
+pub mod filter;
+pub mod frequency;
+#[cfg(feature = "fst")]
+pub mod fst;
+pub mod generate;
+mod join;
+pub mod json;
+pub mod log;
+mod mmap;
+pub mod strategy;
+pub mod task;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+
+pub use bee::{BeeWord, SpellingBee};
+pub use cache::DictionaryCache;
+pub use compiled::CompiledDictionary;
+pub use definitions::Definitions;
+pub use dictionary::Dictionary;
+pub use filter::{DictionaryFilter, Filters, WordFilter};
+pub use frequency::Frequencies;
+#[cfg(feature = "fst")]
+pub use fst::WordSet;
+pub use generate::{Generator, Random};
+pub use strategy::{SearchResult, SearchStrategy};
+pub use task::{solve_async, SolveTask};
+
+use join::CoveringWords;
+
+// the word list that ships with the crate
+pub const DEFAULT_DICTIONARY: &str = "yawl_mendel_lee_cooper_word-list-for-lb.txt";
+
+// the places to look for the default word list, in order: the current
+// directory, next to the executable, then the directory the crate was built from
+pub fn default_dictionary_paths() -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(DEFAULT_DICTIONARY)];
+    if let Some(dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        paths.push(dir.join(DEFAULT_DICTIONARY));
+    }
+    paths.push(Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_DICTIONARY));
+    paths
+}
+
+// the first of the default dictionary paths that exists
+pub fn find_default_dictionary() -> Option<PathBuf> {
+    default_dictionary_paths()
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+// read a whole word list into memory, one word per line, or compiled with
+// `lbsolver dict compile`
+pub fn read_word_list<P: AsRef<Path>>(path: P) -> Result<Vec<String>, LbError> {
+    let path = path.as_ref();
+    let _span = log::Span::new(log::INFO, format_args!("reading {}", path.display()));
+    let words = if CompiledDictionary::is_compiled_file(path) {
+        let dictionary = CompiledDictionary::open(path)?;
+        dictionary
+            .words()
+            .map(|word| lowercase(word).into_owned())
+            .collect()
+    } else {
+        read_words(File::open(path)?)?
+    };
+    log::debug!("{} words in {}", words.len(), path.display());
+    Ok(words)
+}
+
+// read a word list from anywhere, e.g. a file, stdin or bytes already in
+// memory when there's no filesystem to read from. words are lowercased as
+// they're read, the same as the board's letters
+pub fn read_words<R: Read>(source: R) -> Result<Vec<String>, LbError> {
+    let mut words = compiled::read_entries(source)?;
+    for word in &mut words {
+        if let Cow::Owned(lower) = lowercase(word) {
+            *word = lower;
+        }
+    }
+    Ok(words)
+}
+
+// boards and words are matched in lowercase, so "ABC def Ghi jkl" is the
+// same board as "abc def ghi jkl". most words already are, so they're
+// only copied when they need changing
+fn lowercase(word: &str) -> Cow<'_, str> {
+    if word.chars().any(char::is_uppercase) {
+        Cow::Owned(word.to_lowercase())
+    } else {
+        Cow::Borrowed(word)
+    }
+}
+
+// put several word lists together into one, each word only once, in the
+// order they first appear
+pub fn merge_word_lists<I: IntoIterator<Item = Vec<String>>>(lists: I) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut merged = Vec::new();
+    for word in lists.into_iter().flatten() {
+        if seen.insert(word.clone()) {
+            merged.push(word);
+        }
+    }
+    merged
+}
+
+// everything that can go wrong setting up a board and its dictionary, or
+// searching it
+#[derive(Debug)]
+pub enum LbError {
+    // a group of letters on the board wasn't the same length as the others
+    InvalidGroup { group: String, expected: usize },
+    // a board needs at least two sides for words to move between them
+    TooFewGroups(usize),
+    // something other than a letter in a group
+    InvalidLetter { letter: char, group: String },
+    // a letter twice on the same side
+    DuplicateLetter(char),
+    // a board written as one string that couldn't be split into sides
+    InvalidBoard(String),
+    // letter coverage is tracked in a u64, one bit per letter
+    TooManyLetters(usize),
+    // the word list couldn't be opened or read
+    Dictionary(io::Error),
+    // a compiled word list that's cut short or laid out wrong
+    InvalidDictionary(String),
+    // a puzzle or solution read from JSON was missing something
+    InvalidJson(String),
+    // a spelling bee's letters weren't letters, or there weren't enough
+    InvalidBee(String),
+    // a saved search couldn't be read, or was for a different search
+    InvalidCheckpoint(String),
+    // the search ran out of time, or was cancelled, before it found any
+    // solutions. a search that finishes without any isn't an error, the
+    // board just has none with that many words
+    Timeout,
+}
+
+// the name the error had before, so code written against it still builds
+pub type SolverError = LbError;
+
+impl fmt::Display for LbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LbError::InvalidGroup { group, expected } => {
+                write!(
+                    f,
+                    "invalid group {:?}: each group of letters must be {} letters long",
+                    group, expected
+                )
+            }
+            LbError::TooFewGroups(count) => write!(
+                f,
+                "a board needs at least 2 groups of letters, got {}",
+                count
+            ),
+            LbError::InvalidLetter { letter, group } => {
+                write!(f, "invalid group {:?}: {:?} is not a letter", group, letter)
+            }
+            LbError::DuplicateLetter(letter) => {
+                write!(f, "{:?} is on the same side more than once", letter)
+            }
+            LbError::InvalidBoard(message) => write!(f, "invalid board: {}", message),
+            LbError::TooManyLetters(count) => write!(
+                f,
+                "a board can have at most 64 different letters, got {}",
+                count
+            ),
+            LbError::Dictionary(err) => write!(f, "could not read the dictionary: {}", err),
+            LbError::InvalidDictionary(message) => {
+                write!(f, "not a compiled word list: {}", message)
+            }
+            LbError::InvalidJson(message) => write!(f, "invalid JSON: {}", message),
+            LbError::InvalidBee(message) => write!(f, "invalid spelling bee: {}", message),
+            LbError::InvalidCheckpoint(message) => {
+                write!(f, "could not resume the search: {}", message)
+            }
+            LbError::Timeout => write!(f, "the search stopped before it found any solutions"),
+        }
+    }
+}
+
+impl Error for LbError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LbError::InvalidGroup { .. }
+            | LbError::TooFewGroups(_)
+            | LbError::InvalidLetter { .. }
+            | LbError::DuplicateLetter(_)
+            | LbError::InvalidBoard(_)
+            | LbError::TooManyLetters(_)
+            | LbError::InvalidDictionary(_)
+            | LbError::InvalidJson(_)
+            | LbError::InvalidBee(_)
+            | LbError::InvalidCheckpoint(_)
+            | LbError::Timeout => None,
+            LbError::Dictionary(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for LbError {
+    fn from(err: io::Error) -> Self {
+        LbError::Dictionary(err)
+    }
+}
+
+// a letter boxed board: the groups of letters on each side of the square,
+// usually 4 sides of 3 letters but any number of 2 or more sides works,
+// and every side has the same number of letters. the letters are
+// lowercased, so the groups can be typed in any case. a letter can be on
+// more than one side, and then each of its places has to be played
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    letter_groups: Vec<Vec<char>>,
+    available_chars: BTreeSet<char>,
+    group_size: usize,
+    // each place on the board, a letter on a side, gets its own bit, so
+    // the places a word or chain covers can be kept as a single mask.
+    // boards can be bigger than the usual 12 letters so this is a u64
+    // rather than a u16. the places are in letter order, then side order
+    tiles: Vec<Tile>,
+    // every place each letter is on
+    letter_bits: HashMap<char, u64>,
+    full_mask: u64,
+}
+
+// a letter on one side of the board
+#[derive(Debug, Clone, Copy)]
+struct Tile {
+    letter: char,
+    side: usize,
+}
+
+// one way to play a word or chain on the board: the places it starts and
+// ends on, by their index in the puzzle's tiles, and every place it covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct TilePath {
+    pub first: usize,
+    pub last: usize,
+    pub mask: u64,
+}
+
+impl Puzzle {
+    // the number of letters per side is taken from the first group
+    pub fn new(string_groups: &[String]) -> Result<Puzzle, LbError> {
+        let group_size = string_groups
+            .first()
+            .map_or(0, |group| group.chars().count());
+        Puzzle::with_group_size(string_groups, group_size)
+    }
+
+    pub fn with_group_size(string_groups: &[String], group_size: usize) -> Result<Puzzle, LbError> {
+        if string_groups.len() < 2 {
+            return Err(LbError::TooFewGroups(string_groups.len()));
+        }
+
+        let mut letter_groups: Vec<Vec<char>> = Vec::new();
+        let mut available_chars: BTreeSet<char> = BTreeSet::new();
+        let mut tiles: Vec<Tile> = Vec::new();
+
+        for group in string_groups {
+            let mut chars: Vec<char> = lowercase(group).chars().collect();
+            if chars.len() != group_size || group_size == 0 {
+                return Err(LbError::InvalidGroup {
+                    group: group.clone(),
+                    expected: group_size.max(1),
+                });
+            }
+            if let Some(&letter) = chars.iter().find(|c| !c.is_alphabetic()) {
+                return Err(LbError::InvalidLetter {
+                    letter,
+                    group: group.clone(),
+                });
+            }
+            chars.sort();
+            if let Some(pair) = chars.windows(2).find(|pair| pair[0] == pair[1]) {
+                return Err(LbError::DuplicateLetter(pair[0]));
+            }
+            let side = letter_groups.len();
+            tiles.extend(chars.iter().map(|&letter| Tile { letter, side }));
+            available_chars.extend(&chars);
+            letter_groups.push(chars);
+        }
+
+        if tiles.len() > 64 {
+            return Err(LbError::TooManyLetters(tiles.len()));
+        }
+
+        tiles.sort_by_key(|tile| (tile.letter, tile.side));
+        let mut letter_bits: HashMap<char, u64> = HashMap::new();
+        for (i, tile) in tiles.iter().enumerate() {
+            *letter_bits.entry(tile.letter).or_default() |= 1 << i;
+        }
+        let full_mask = letter_bits.values().fold(0, |mask, bit| mask | bit);
+
+        Ok(Puzzle {
+            letter_groups,
+            available_chars,
+            group_size,
+            tiles,
+            letter_bits,
+            full_mask,
+        })
+    }
+
+    pub fn letter_groups(&self) -> &[Vec<char>] {
+        &self.letter_groups
+    }
+
+    // the letters on the board, in order
+    pub fn available_chars(&self) -> &BTreeSet<char> {
+        &self.available_chars
+    }
+
+    pub fn side_count(&self) -> usize {
+        self.letter_groups.len()
+    }
+
+    pub fn group_size(&self) -> usize {
+        self.group_size
+    }
+
+    // how many letters are on the board, 12 for the usual 4 sides of 3
+    pub fn letter_count(&self) -> usize {
+        self.side_count() * self.group_size
+    }
+
+    // which side a letter is on, counting from 0. the first one, for a
+    // letter on more than one side
+    pub fn side_of(&self, c: char) -> Option<usize> {
+        self.letter_groups
+            .iter()
+            .position(|group| group.contains(&c))
+    }
+
+    // whether any letter is on more than one side
+    pub fn has_duplicates(&self) -> bool {
+        self.tiles.len() != self.available_chars.len()
+    }
+
+    // the board places a word uses, one bit per place. a letter on more
+    // than one side counts all of its places, `chain_mask` works out
+    // which ones a chain can actually reach
+    pub fn letter_mask(&self, word: &str) -> u64 {
+        word.chars()
+            .filter_map(|c| self.letter_bits.get(&c))
+            .fold(0, |mask, bit| mask | bit)
+    }
+
+    // the places a chain covers, played whichever way covers the most.
+    // for a chain that can't be played at all, every place its letters are
+    // on, the same as `letter_mask`
+    pub fn chain_mask(&self, words: &[String]) -> u64 {
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        match self.best_path(&words) {
+            Some(path) => path.mask,
+            None => words
+                .iter()
+                .fold(0, |mask, word| mask | self.letter_mask(word)),
+        }
+    }
+
+    // the way of playing a chain that covers the most places
+    pub(crate) fn best_path(&self, words: &[&str]) -> Option<TilePath> {
+        self.tile_paths(words)
+            .into_iter()
+            .max_by_key(|path| (path.mask.count_ones(), std::cmp::Reverse(*path)))
+    }
+
+    // every way of playing a chain of words, moving from place to place
+    // on different sides, with each word starting on the place the one
+    // before it ended. on a board without duplicate letters there's one
+    // way at most
+    pub(crate) fn tile_paths(&self, words: &[&str]) -> BTreeSet<TilePath> {
+        let mut paths: BTreeSet<TilePath> = BTreeSet::new();
+        let mut letters = words
+            .iter()
+            .enumerate()
+            .flat_map(|(i, word)| word.chars().skip(usize::from(i > 0)));
+        let first_letter = match letters.next() {
+            Some(c) => c,
+            None => return paths,
+        };
+        for (t, tile) in self.tiles.iter().enumerate() {
+            if tile.letter == first_letter {
+                paths.insert(TilePath {
+                    first: t,
+                    last: t,
+                    mask: 1 << t,
+                });
+            }
+        }
+        // a word has to start with the letter the one before it ended with
+        let linked = words
+            .windows(2)
+            .all(|pair| pair[0].chars().last() == pair[1].chars().next());
+        if !linked {
+            return BTreeSet::new();
+        }
+        for c in letters {
+            let mut next = BTreeSet::new();
+            for path in &paths {
+                for (t, tile) in self.tiles.iter().enumerate() {
+                    if tile.letter == c && tile.side != self.tiles[path.last].side {
+                        next.insert(TilePath {
+                            last: t,
+                            mask: path.mask | 1 << t,
+                            ..*path
+                        });
+                    }
+                }
+            }
+            paths = next;
+        }
+        paths
+    }
+
+    // what the start and end letter dictionaries know a place by: its
+    // letter, unless the letter is on more than one side, when each place
+    // gets a character of its own from the private use area
+    pub(crate) fn tile_key(&self, t: usize) -> char {
+        let letter = self.tiles[t].letter;
+        if self.letter_bits[&letter].count_ones() == 1 {
+            letter
+        } else {
+            char::from_u32(0xe000 + t as u32).unwrap_or(letter)
+        }
+    }
+
+    // the mask a chain has to reach to be a solution
+    pub fn full_mask(&self) -> u64 {
+        self.full_mask
+    }
+
+    // the board as {"sides": ["abc", ...], "letters_per_side": 3}, which
+    // `from_json` reads back. the letters on each side are sorted
+    pub fn to_json(&self) -> json::Value {
+        let sides: Vec<String> = self
+            .letter_groups
+            .iter()
+            .map(|group| group.iter().collect())
+            .collect();
+        json::Value::object()
+            .with("sides", sides)
+            .with("letters_per_side", self.group_size)
+    }
+
+    // letters_per_side is optional, it's taken from the first side if it's
+    // not given
+    pub fn from_json(value: &json::Value) -> Result<Puzzle, LbError> {
+        let sides = value
+            .get("sides")
+            .and_then(json::Value::as_strings)
+            .ok_or_else(|| LbError::InvalidJson("a puzzle needs a list of sides".to_string()))?;
+        match value.get("letters_per_side") {
+            Some(size) => {
+                let size = size.as_f64().ok_or_else(|| {
+                    LbError::InvalidJson("letters_per_side must be a number".to_string())
+                })?;
+                Puzzle::with_group_size(&sides, size as usize)
+            }
+            None => Puzzle::new(&sides),
+        }
+    }
+
+    // check a proposed solution against each of the game's rules, with
+    // `is_word` saying whether a word is in the word list
+    pub fn check<F: Fn(&str) -> bool>(&self, words: &[String], is_word: F) -> Vec<RuleCheck> {
+        let rule = |rule, problems| RuleCheck { rule, problems };
+
+        let not_words = words
+            .iter()
+            .filter(|word| !is_word(word))
+            .map(|word| format!("{} is not in the word list", word))
+            .collect();
+
+        let too_short = words
+            .iter()
+            .filter(|word| word.chars().count() < 3)
+            .map(|word| format!("{} is shorter than 3 letters", word))
+            .collect();
+
+        let mut off_board = Vec::new();
+        for word in words {
+            let chars: Vec<char> = word.chars().collect();
+            let missing: BTreeSet<&char> = chars
+                .iter()
+                .filter(|c| !self.available_chars.contains(c))
+                .collect();
+            for c in missing {
+                off_board.push(format!("{}: {} is not on the board", word, c));
+            }
+            for pair in chars.windows(2) {
+                let on_board = pair.iter().all(|c| self.available_chars.contains(c));
+                let pair_word: String = pair.iter().collect();
+                if on_board && !self.is_letter_pattern_in_letter_box(&pair_word) {
+                    off_board.push(format!(
+                        "{}: {} and {} are on the same side",
+                        word, pair[0], pair[1]
+                    ));
+                }
+            }
+        }
+
+        let broken_links = words
+            .windows(2)
+            .filter_map(|pair| {
+                let end = pair[0].chars().last();
+                let start = pair[1].chars().next();
+                (end != start).then(|| {
+                    format!(
+                        "{} ends with {} but {} starts with {}",
+                        pair[0],
+                        end.unwrap_or(' '),
+                        pair[1],
+                        start.unwrap_or(' ')
+                    )
+                })
+            })
+            .collect();
+
+        let covered = self.chain_mask(words);
+        let missing: Vec<String> = self
+            .available_chars
+            .iter()
+            .filter(|c| covered & self.letter_bits[c] != self.letter_bits[c])
+            .map(char::to_string)
+            .collect();
+        let uncovered = if missing.is_empty() {
+            Vec::new()
+        } else {
+            vec![format!("{} not covered", missing.join(", "))]
+        };
+
+        vec![
+            rule("every word is in the word list", not_words),
+            rule("every word is at least 3 letters long", too_short),
+            rule(
+                "every letter is on the board, and no two in a row are on the same side",
+                off_board,
+            ),
+            rule(
+                "each word starts with the letter the one before it ended with",
+                broken_links,
+            ),
+            rule("every letter on the board is used", uncovered),
+        ]
+    }
+
+    // what each word of a chain adds to it, to show why it solves the board
+    pub fn explain(&self, words: &[String]) -> Vec<WordCoverage> {
+        let mut covered = 0;
+        words
+            .iter()
+            .zip(self.played_letters(words))
+            .map(|(word, letters)| {
+                covered += letters.iter().filter(|letter| letter.new).count();
+                WordCoverage {
+                    word: word.clone(),
+                    new_letters: letters
+                        .iter()
+                        .filter(|letter| letter.new)
+                        .map(|letter| letter.letter)
+                        .collect(),
+                    sides: letters.iter().filter_map(|letter| letter.side).collect(),
+                    covered,
+                }
+            })
+            .collect()
+    }
+
+    // each letter of each word in a chain, with the side it's played on
+    // and whether it covers a place the chain hadn't yet. a letter on more
+    // than one side is on the place the chain is played through, the way
+    // `chain_mask` picks. a chain that can't be played is taken a letter at
+    // a time, with each letter on the first side it's on and covering all
+    // of its places at once
+    pub fn played_letters(&self, words: &[String]) -> Vec<Vec<PlayedLetter>> {
+        let chain: Vec<&str> = words.iter().map(String::as_str).collect();
+        let tiles = self.played_tiles(&chain);
+        let mut coverage = 0;
+        // where each word starts among the letters played, each one
+        // starting on the letter the word before it ended on
+        let mut start = 0;
+        words
+            .iter()
+            .map(|word| {
+                let letters = word
+                    .chars()
+                    .enumerate()
+                    .map(|(j, c)| {
+                        let (side, bit) = match &tiles {
+                            Some(tiles) => {
+                                let t = tiles[start + j];
+                                (Some(self.tiles[t].side), 1 << t)
+                            }
+                            None => (self.side_of(c), self.letter_mask(&c.to_string())),
+                        };
+                        let new = bit != 0 && coverage & bit != bit;
+                        coverage |= bit;
+                        PlayedLetter {
+                            letter: c,
+                            side,
+                            new,
+                        }
+                    })
+                    .collect();
+                start += word.chars().count().saturating_sub(1);
+                letters
+            })
+            .collect()
+    }
+
+    // the place each letter of a chain is played on, the linking letters
+    // once, for the way `best_path` picks. none if it can't be played
+    fn played_tiles(&self, words: &[&str]) -> Option<Vec<usize>> {
+        let linked = words
+            .windows(2)
+            .all(|pair| pair[0].chars().last() == pair[1].chars().next());
+        if !linked {
+            return None;
+        }
+        let mut letters = words
+            .iter()
+            .enumerate()
+            .flat_map(|(i, word)| word.chars().skip(usize::from(i > 0)));
+        let first_letter = letters.next()?;
+        // the same ways as `tile_paths`, each with the places it went through
+        let mut paths: BTreeMap<TilePath, Vec<usize>> = BTreeMap::new();
+        for (t, tile) in self.tiles.iter().enumerate() {
+            if tile.letter == first_letter {
+                let path = TilePath {
+                    first: t,
+                    last: t,
+                    mask: 1 << t,
+                };
+                paths.insert(path, vec![t]);
+            }
+        }
+        for c in letters {
+            let mut next = BTreeMap::new();
+            for (path, tiles) in &paths {
+                for (t, tile) in self.tiles.iter().enumerate() {
+                    if tile.letter == c && tile.side != self.tiles[path.last].side {
+                        let path = TilePath {
+                            last: t,
+                            mask: path.mask | 1 << t,
+                            ..*path
+                        };
+                        next.entry(path).or_insert_with(|| {
+                            let mut tiles = tiles.clone();
+                            tiles.push(t);
+                            tiles
+                        });
+                    }
+                }
+            }
+            paths = next;
+        }
+        paths
+            .into_iter()
+            .max_by_key(|(path, _)| (path.mask.count_ones(), std::cmp::Reverse(*path)))
+            .map(|(_, tiles)| tiles)
+    }
+
+    // take the first two letters, and check they are in different
+    // groups, then move along the word one letter at a time
+    pub fn is_letter_pattern_in_letter_box(&self, word: &str) -> bool {
+        // with a letter on more than one side it depends which one is used
+        if self.has_duplicates() {
+            return !self.tile_paths(&[word]).is_empty();
+        }
+        let chars: Vec<char> = word.chars().collect();
+        for pair in chars.windows(2) {
+            for group in &self.letter_groups {
+                if group.contains(&pair[0]) && group.contains(&pair[1]) {
+                    // if we get here then the two letters are in the same group
+                    // which won't work
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+// the board's own filter: words of at least 3 letters, all of them on the
+// board, that never go from a side to the same side. letters can be reused
+// so there's no upper limit on the length. lengths are in letters, not
+// bytes, so words with accents count right
+impl WordFilter for Puzzle {
+    fn accept(&self, word: &str) -> bool {
+        word.chars().count() >= 3
+            && word.chars().all(|c| self.available_chars.contains(&c))
+            && self.is_letter_pattern_in_letter_box(word)
+    }
+}
+
+// a board written as one string, the way boards are usually shared: the
+// sides separated by dashes, slashes, commas or spaces ("abc-def-ghi-jkl"),
+// or just the letters, which are split into this many sides of the same
+// length ("abcdefghijkl")
+pub fn split_board(board: &str, sides: usize) -> Result<Vec<String>, LbError> {
+    let is_separator = |c: char| matches!(c, '-' | '/' | ',') || c.is_whitespace();
+    let board = board.trim();
+    if board.contains(is_separator) {
+        return Ok(board
+            .split(is_separator)
+            .filter(|group| !group.is_empty())
+            .map(str::to_string)
+            .collect());
+    }
+    let letters: Vec<char> = board.chars().collect();
+    if letters.is_empty() || sides == 0 || !letters.len().is_multiple_of(sides) {
+        return Err(LbError::InvalidBoard(format!(
+            "{} letters can't be split into {} sides of the same length",
+            letters.len(),
+            sides
+        )));
+    }
+    Ok(letters
+        .chunks(letters.len() / sides)
+        .map(|side| side.iter().collect())
+        .collect())
+}
+
+// the usual 4 sides, as `split_board` reads them
+impl FromStr for Puzzle {
+    type Err = LbError;
+
+    fn from_str(board: &str) -> Result<Puzzle, LbError> {
+        Puzzle::new(&split_board(board, 4)?)
+    }
+}
+
+// the sides with dashes between them, which `from_str` reads back
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sides: Vec<String> = self
+            .letter_groups
+            .iter()
+            .map(|group| group.iter().collect())
+            .collect();
+        write!(f, "{}", sides.join("-"))
+    }
+}
+
+// what a word adds to a chain: the board letters it's the first to use,
+// and the side each of its letters is on, in order, so every move from one
+// side to another can be seen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordCoverage {
+    pub word: String,
+    pub new_letters: Vec<char>,
+    // indexes into the board's groups of letters
+    pub sides: Vec<usize>,
+    // how many board letters the chain has used once this word is played
+    pub covered: usize,
+}
+
+// a letter of a chain as it's played, from `Puzzle::played_letters`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayedLetter {
+    pub letter: char,
+    // the index of the side it's played on, none if it's not on the board
+    pub side: Option<usize>,
+    // whether it covers a place on the board for the first time
+    pub new: bool,
+}
+
+impl WordCoverage {
+    pub fn to_json(&self) -> json::Value {
+        json::Value::object()
+            .with("word", self.word.as_str())
+            .with("new_letters", self.new_letters.clone())
+            .with("sides", self.sides.clone())
+            .with("covered", self.covered)
+    }
+}
+
+// one of the rules a proposed solution has to follow, and what broke it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleCheck {
+    pub rule: &'static str,
+    // a line for each thing that broke the rule, empty if it passed
+    pub problems: Vec<String>,
+}
+
+impl RuleCheck {
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    pub fn to_json(&self) -> json::Value {
+        json::Value::object()
+            .with("rule", self.rule)
+            .with("passed", self.passed())
+            .with("problems", self.problems.clone())
+    }
+}
+
+// a chain of words that covers every letter on the board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub words: Vec<String>,
+    // the total number of letters across all the words, fewer is better
+    pub letter_count: usize,
+}
+
+impl Solution {
+    pub fn new(words: Vec<String>) -> Solution {
+        let letter_count = words.iter().map(|word| word.chars().count()).sum();
+        Solution {
+            words,
+            letter_count,
+        }
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.words.len()
+    }
+
+    // how many letters get played more than once, as they're typed into
+    // the game where the letter joining two words is only played once
+    pub fn reused_letters(&self) -> usize {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for (i, word) in self.words.iter().enumerate() {
+            for c in word.chars().skip(usize::from(i > 0)) {
+                *counts.entry(c).or_default() += 1;
+            }
+        }
+        counts.values().filter(|&&count| count > 1).count()
+    }
+
+    // the in-game ranking: fewest words, then fewest letters, then the
+    // fewest letters played twice
+    pub fn cmp_by_letters(&self, other: &Solution) -> Ordering {
+        self.word_count()
+            .cmp(&other.word_count())
+            .then(self.letter_count.cmp(&other.letter_count))
+            .then(self.reused_letters().cmp(&other.reused_letters()))
+    }
+
+    // fewest words first, then fewest letters, then alphabetical, so
+    // solutions with as many words still come out in a fixed order
+    pub fn cmp_by_words(&self, other: &Solution) -> Ordering {
+        self.word_count()
+            .cmp(&other.word_count())
+            .then(self.letter_count.cmp(&other.letter_count))
+            .then_with(|| self.cmp_alphabetically(other))
+    }
+
+    // alphabetical by the first word, then the second and so on
+    pub fn cmp_alphabetically(&self, other: &Solution) -> Ordering {
+        self.words.cmp(&other.words)
+    }
+
+    pub fn to_json(&self) -> json::Value {
+        json::Value::object()
+            .with("words", self.words.clone())
+            .with("word_count", self.word_count())
+            .with("letter_count", self.letter_count)
+    }
+
+    // the words in alphabetical order, the same for every ordering of them
+    pub fn sorted_words(&self) -> Vec<String> {
+        let mut words = self.words.clone();
+        words.sort();
+        words
+    }
+
+    // read back a solution written by `to_json`, only its words are needed
+    pub fn from_json(value: &json::Value) -> Result<Solution, LbError> {
+        value
+            .get("words")
+            .and_then(json::Value::as_strings)
+            .map(Solution::new)
+            .ok_or_else(|| LbError::InvalidJson("a solution needs a list of words".to_string()))
+    }
+}
+
+#[derive(PartialEq, Eq)]
+struct State {
+    // indexes into the solver's dictionary
+    chain: Vec<usize>,
+    // a bit for every word index in the chain, folded into 64 bits, so most
+    // words can be ruled out of the chain without looking through it
+    used_words: u64,
+    // the board letters the chain has used so far
+    coverage: u64,
+    // how many of the required words aren't in the chain yet, kept up to
+    // date as words are added so the chain never has to be searched
+    missing_required: usize,
+    cost: usize,
+    heuristic: usize,
+    // the cost plus the weighted heuristic, in thousandths of a word so
+    // states can still be compared exactly
+    estimate: u64,
+    // what the solver's cost function makes of the chain, 0 without one
+    score: u64,
+}
+
+// states with the same number of words to go are taken cheapest chain
+// first by the cost function, if there is one, then the one that covers
+// the most letters. states that tie on those too come off the heap in an
+// order that only depends on the order they went on, which is the same on
+// every run since the dictionary and letter indexes are all kept in order
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            .then(other.score.cmp(&self.score))
+            .then(self.coverage.count_ones().cmp(&other.coverage.count_ones()))
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// the chain IDA* is looking at, a frame per word
+struct Ida {
+    first_words: Vec<usize>,
+    // the next of the first words to start a chain from
+    next_first: usize,
+    // the letters covered before the first word, e.g. by words already played
+    coverage: u64,
+    required_count: usize,
+    // whether to start again a word deeper when the chains run out, or
+    // search every chain up to the most words in one go
+    deepen: bool,
+    stack: Vec<Frame>,
+}
+
+struct Frame {
+    word: usize,
+    coverage: u64,
+    missing_required: usize,
+    // how far through the words that can follow this one the search is
+    next: usize,
+}
+
+impl Ida {
+    // a word added to a chain with this coverage and missing words
+    fn frame(
+        &self,
+        solver: &Solver,
+        word: usize,
+        coverage: u64,
+        missing_required: usize,
+        required: &[bool],
+    ) -> Frame {
+        Frame {
+            word,
+            coverage: coverage | solver.words.word_masks[word],
+            missing_required: missing_required - usize::from(required[word]),
+            next: 0,
+        }
+    }
+
+    // whether a chain of this length ending in the frame could still be
+    // finished within the depth. the heuristic never counts more words
+    // than a chain needs, so this never rules out one that could finish
+    fn fits(&self, solver: &Solver, frame: &Frame, length: usize, depth: usize) -> bool {
+        length + solver.heuristic(frame.coverage, frame.missing_required, frame.word) <= depth
+    }
+}
+
+// how far a search has got, handed to the progress callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    // how many chains have been extended with another word
+    pub nodes_expanded: usize,
+    // the number of words in the chains being searched
+    pub depth: usize,
+    // how many chains are waiting to be searched
+    pub queue_size: usize,
+    // the most board letters any chain has covered so far
+    pub best_coverage: usize,
+    // how many letters there are to cover
+    pub letter_count: usize,
+}
+
+// what a search did, from `run_solver_with_stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    // how many chains were extended with another word
+    pub nodes_expanded: usize,
+    // how many chains were dropped without being searched: ones that
+    // repeat or ignore a word, ones too long to finish in time, and the
+    // ones Sma forgot
+    pub nodes_pruned: usize,
+    // the most chains waiting to be searched at once, added up across
+    // threads
+    pub peak_queue_size: usize,
+    // whether the solutions were joined straight from the word indexes,
+    // so there was nothing to search
+    pub joined: bool,
+    // whether the search stopped before it was finished, from the
+    // timeout, the cancel token or running out of expansions
+    pub stopped: bool,
+    // how long it took to find the solutions, and to rank them after
+    pub search_time: Duration,
+    pub ranking_time: Duration,
+}
+
+impl SearchStats {
+    // the counts from two threads' searches
+    fn add(&mut self, other: &SearchStats) {
+        self.nodes_expanded += other.nodes_expanded;
+        self.nodes_pruned += other.nodes_pruned;
+        self.peak_queue_size += other.peak_queue_size;
+        self.stopped |= other.stopped;
+    }
+}
+
+// how many chains are expanded between calls to the progress callback
+const PROGRESS_INTERVAL: usize = 10_000;
+
+// how many chains Sma keeps waiting, unless it's told otherwise
+pub const DEFAULT_QUEUE_LIMIT: usize = 1_000_000;
+
+// how many chains Beam keeps at each length, unless it's told otherwise
+pub const DEFAULT_BEAM_WIDTH: usize = 1000;
+
+// how often a search with a checkpoint file saves its state
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+// a progress callback, in its own type so the solver can still be Debug
+#[derive(Clone)]
+struct ProgressCallback(Arc<dyn Fn(&Progress) + Send + Sync>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+type ChainCost = dyn Fn(&[&str]) -> u64 + Send + Sync;
+
+#[derive(Clone)]
+struct CostFunction(Arc<ChainCost>);
+
+impl fmt::Debug for CostFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CostFunction")
+    }
+}
+
+// a strategy set with `set_strategy`, in its own type so the solver can
+// still be Debug
+#[derive(Clone)]
+struct CustomStrategy(Arc<dyn SearchStrategy>);
+
+impl fmt::Debug for CustomStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CustomStrategy")
+    }
+}
+
+type WordCheck = dyn Fn(&str, &[&str]) -> bool + Send + Sync;
+
+#[derive(Clone)]
+struct WordValidator(Arc<WordCheck>);
+
+impl fmt::Debug for WordValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WordValidator")
+    }
+}
+
+// stops a search from another thread, e.g. when the user gives up on it.
+// clones share the same flag
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, AtomicOrdering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(AtomicOrdering::Relaxed)
+    }
+}
+
+// picks the words that fit a board out of a word list, one at a time, so
+// the list never has to be held in memory. the cheapest checks go first,
+// and a word is only copied once it's passed all of them
+struct FittingWords<'a> {
+    puzzle: &'a Puzzle,
+    // the board's a to z letters as bits
+    board: u32,
+    // the caller's own filter, checked after the board's
+    filter: Option<&'a dyn WordFilter>,
+    words: Vec<String>,
+}
+
+impl<'a> FittingWords<'a> {
+    fn new(puzzle: &'a Puzzle, filter: Option<&'a dyn WordFilter>) -> FittingWords<'a> {
+        let board = puzzle
+            .available_chars
+            .iter()
+            .filter_map(|&c| dictionary::ascii_bit(c))
+            .fold(0, |mask, bit| mask | bit);
+        FittingWords {
+            puzzle,
+            board,
+            filter,
+            words: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, word: &str) {
+        // any a to z letter, in either case, that isn't on the board rules
+        // the word out, and so does anything else ascii, since boards are
+        // only letters. other letters are left to the full check
+        let fits_board = word.bytes().all(|b| {
+            if b.is_ascii_alphabetic() {
+                self.board & (1 << (b.to_ascii_lowercase() - b'a')) != 0
+            } else {
+                !b.is_ascii()
+            }
+        });
+        if !fits_board {
+            return;
+        }
+        let word = lowercase(word);
+        if self.puzzle.accept(&word) && self.filter.is_none_or(|filter| filter.accept(&word)) {
+            self.words.push(word.into_owned());
+        }
+    }
+
+    // the words, each once in the order they first came. a word that can
+    // be played more than one way on a board with a letter on more than
+    // one side is kept once for each way
+    fn finish(self) -> Vec<String> {
+        let mut words = self.words;
+        // a word can be in a list in more than one case
+        let mut seen = HashSet::new();
+        let first: Vec<bool> = words
+            .iter()
+            .map(|word| seen.insert(word.as_str()))
+            .collect();
+        let mut first = first.into_iter();
+        words.retain(|_| first.next().unwrap_or(false));
+        if !self.puzzle.has_duplicates() {
+            return words;
+        }
+        words
+            .into_iter()
+            .flat_map(|word| {
+                let ways = self.puzzle.tile_paths(&[word.as_str()]).len();
+                std::iter::repeat_n(word, ways)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Solver {
+    puzzle: Puzzle,
+    // the words that fit the board and their indexes. they don't change
+    // once they're built, so clones of the solver, set up differently or
+    // searching on other threads, share them
+    words: Arc<WordIndex>,
+    threads: usize,
+    // words every solution has to include
+    required_words: Vec<String>,
+    // the word every solution has to start with
+    start_word: Option<String>,
+    // the longest chain the search will try
+    max_words: usize,
+    // called every so often during long searches
+    progress: Option<ProgressCallback>,
+    // scores chains with the same number of words, lower is better
+    cost: Option<CostFunction>,
+    // can turn a word down as it's about to go on the end of a chain
+    validator: Option<WordValidator>,
+    // how long a search can run for before it gives up
+    timeout: Option<Duration>,
+    // how many chains it can expand before it gives up
+    max_nodes: Option<usize>,
+    cancel: Option<CancelToken>,
+    // how many solutions to return, none for the default
+    limit: Option<usize>,
+    // return every solution with the fewest words, not just the first few
+    all: bool,
+    algorithm: Algorithm,
+    // searches instead of the algorithm's own strategy, when it's set
+    strategy: Option<CustomStrategy>,
+    heuristic: Heuristic,
+    // how much more the heuristic counts than the words so far
+    weight: f64,
+    // the most chains Sma keeps waiting to be extended
+    queue_limit: usize,
+    // how many chains Beam keeps at each length
+    beam_width: usize,
+    // where the search saves its state every so often, to be resumed from
+    checkpoint: Option<PathBuf>,
+}
+
+// the words that fit a board, and the indexes the search uses to get
+// from word to word
+#[derive(Debug)]
+struct WordIndex {
+    // a word that can be played more than one way, when a letter is on
+    // more than one side, is in here once for each way, one after another
+    dictionary: Vec<String>,
+    // the board places each dictionary word uses
+    word_masks: Vec<u64>,
+    // what the start and end letter dictionaries know the place each word
+    // starts and ends on by, see `Puzzle::tile_key`
+    word_ends: Vec<(char, char)>,
+    // the index of each word's first copy, so a chain doesn't use the same
+    // word twice by playing it another way
+    word_ids: Vec<usize>,
+    // ordered maps rather than hash maps, so anything that walks them
+    // does it in the same order on every run
+    start_letter_dictionary: BTreeMap<char, Vec<usize>>,
+    end_letter_dictionary: BTreeMap<char, Vec<usize>>,
+    // the words that can follow each word, so the search can go straight
+    // from a word to a slice of the ones after it. every word ending in
+    // the same letter has the same followers, so they're kept once per
+    // letter, one after another, and each word has its range of them
+    followers: Vec<usize>,
+    follower_ranges: Vec<Range<usize>>,
+    // the most new letters a word can add to a chain: its own letters
+    // bar the first, which the word before it has already covered. once
+    // for each word's followers, and once for any word at all
+    follower_cover: Vec<usize>,
+    max_cover: usize,
+    // for each board place, every place that shares a word with it, and
+    // the places in the order the cover heuristic tries them, the ones
+    // that share a word with the fewest others first
+    together: Vec<u64>,
+    cover_order: Vec<u32>,
+    // which sets of letters the words starting with each letter can
+    // finish, on their own or with one more word after them, none when the
+    // board has too many letters for the tables
+    covering: Option<CoveringWords>,
+}
+
+impl WordIndex {
+    // work out the letter masks and the start and end letter dictionaries,
+    // which refer to words by their index in the dictionary
+    fn new(puzzle: &Puzzle, dictionary: Vec<String>) -> WordIndex {
+        let mut word_masks = Vec::new();
+        let mut word_ends = Vec::new();
+        let mut word_ids = Vec::new();
+        // the nth copy of a word is played the nth way
+        let mut ways: Vec<TilePath> = Vec::new();
+        let mut copy = 0;
+        for (i, word) in dictionary.iter().enumerate() {
+            if !puzzle.has_duplicates() {
+                word_ids.push(i);
+                word_masks.push(puzzle.letter_mask(word));
+                let start_letter = word.chars().next().unwrap();
+                let end_letter = word.chars().last().unwrap();
+                word_ends.push((start_letter, end_letter));
+                continue;
+            }
+            if i > 0 && dictionary[i - 1] == *word {
+                copy += 1;
+            } else {
+                ways = puzzle.tile_paths(&[word]).into_iter().collect();
+                copy = 0;
+            }
+            let way = ways[copy];
+            word_ids.push(i - copy);
+            word_masks.push(way.mask);
+            word_ends.push((puzzle.tile_key(way.first), puzzle.tile_key(way.last)));
+        }
+
+        let mut start_letter_dictionary: BTreeMap<char, Vec<usize>> = BTreeMap::new();
+        let mut end_letter_dictionary: BTreeMap<char, Vec<usize>> = BTreeMap::new();
+
+        for (i, &(start_letter, end_letter)) in word_ends.iter().enumerate() {
+            // add to start letter dictionary
+            start_letter_dictionary
+                .entry(start_letter)
+                .or_default()
+                .push(i);
+
+            // add to end letter dictionary
+            end_letter_dictionary.entry(end_letter).or_default().push(i);
+        }
+
+        if log::enabled(log::DEBUG) {
+            for (letter, words) in &start_letter_dictionary {
+                log::debug!(
+                    "{}: {} words start with it, {} end with it",
+                    letter,
+                    words.len(),
+                    end_letter_dictionary.get(letter).map_or(0, Vec::len)
+                );
+            }
+        }
+
+        let mut followers = Vec::new();
+        let mut letter_ranges: BTreeMap<char, Range<usize>> = BTreeMap::new();
+        for (&letter, words) in &start_letter_dictionary {
+            letter_ranges.insert(letter, followers.len()..followers.len() + words.len());
+            followers.extend(words);
+        }
+        let follower_ranges: Vec<Range<usize>> = word_ends
+            .iter()
+            .map(|(_, end_letter)| letter_ranges.get(end_letter).cloned().unwrap_or(0..0))
+            .collect();
+
+        let new_letters = |i: usize| (word_masks[i].count_ones() as usize).saturating_sub(1);
+        let follower_cover = follower_ranges
+            .iter()
+            .map(|range| {
+                followers[range.clone()]
+                    .iter()
+                    .map(|&i| new_letters(i))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let max_cover = (0..dictionary.len()).map(new_letters).max().unwrap_or(0);
+
+        let mut together = vec![0; 64];
+        for &mask in &word_masks {
+            let mut places = mask;
+            while places != 0 {
+                together[places.trailing_zeros() as usize] |= mask;
+                places &= places - 1;
+            }
+        }
+        let mut cover_order: Vec<u32> = (0..64)
+            .filter(|&place| puzzle.full_mask() & (1 << place) != 0)
+            .collect();
+        cover_order.sort_by_key(|&place| together[place as usize].count_ones());
+        let covering = CoveringWords::new(
+            &word_masks,
+            &word_ends,
+            &start_letter_dictionary,
+            &end_letter_dictionary,
+            puzzle.full_mask().count_ones(),
+        );
+
+        WordIndex {
+            dictionary,
+            word_masks,
+            word_ends,
+            word_ids,
+            start_letter_dictionary,
+            end_letter_dictionary,
+            followers,
+            follower_ranges,
+            follower_cover,
+            max_cover,
+            together,
+            cover_order,
+            covering,
+        }
+    }
+}
+
+// how the search looks for chains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    // best first, a depth at a time. fast, but every chain it's waiting to
+    // extend is kept in memory, which can be a lot on hard boards
+    #[default]
+    AStar,
+    // iterative deepening A*: depth first through every chain up to the
+    // current depth, starting again a word deeper each time. only the
+    // chain being looked at is kept, so memory stays small however hard
+    // the board is, at the cost of going over the shorter chains again
+    Ida,
+    // depth first through every chain up to the most words at once,
+    // cutting off chains that can't cover the letters they're missing in
+    // the words they have left. once a solution is found only chains as
+    // short as it are looked at. simpler than IDA*, and often faster when
+    // every solution of a given length is wanted, e.g. with --max-words
+    Dfs,
+    // A* with a limit on how many chains it keeps waiting, a simple take
+    // on SMA*: when the queue's full the chains that look least likely to
+    // lead anywhere are forgotten. memory stays bounded on hard boards, but
+    // a forgotten chain might have been the start of the best solution
+    Sma,
+    // breadth first a word at a time, only keeping the chains that look
+    // best at each length. time and memory are bounded by how many it
+    // keeps, but like Sma it can miss the best solution
+    Beam,
+    // only the chains of up to three words joined straight from the word
+    // indexes, which every other algorithm tries first. no search at all,
+    // so boards without a chain that short have no solutions
+    Join,
+}
+
+// how the search works out the fewest more words a chain needs. both are
+// lower bounds, so neither stops the search finding the shortest chains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Heuristic {
+    // the letters still missing shared out between the most letters any
+    // one word can add
+    #[default]
+    Letters,
+    // the larger of that and a lower bound on the set cover: missing
+    // letters that never share a word each need a word of their own, so a
+    // set of them picked greedily, rarest first, counts a word each. it
+    // takes longer to work out for each chain, but rules out many more on
+    // hard boards
+    Cover,
+}
+
+impl Solver {
+    // open the word list at `path` and build a solver for the puzzle from it
+    // a compiled word list is mapped rather than read, and only the words
+    // that fit the board are copied out of it
+    pub fn open<P: AsRef<Path>>(puzzle: Puzzle, path: P) -> Result<Solver, LbError> {
+        Solver::open_filtered(puzzle, path.as_ref(), None)
+    }
+
+    // the same as `open`, only keeping the words the filter accepts too.
+    // they're checked as the list is read, so the rest are never copied
+    pub fn open_with<P: AsRef<Path>>(
+        puzzle: Puzzle,
+        path: P,
+        filter: &dyn WordFilter,
+    ) -> Result<Solver, LbError> {
+        Solver::open_filtered(puzzle, path.as_ref(), Some(filter))
+    }
+
+    fn open_filtered(
+        puzzle: Puzzle,
+        path: &Path,
+        filter: Option<&dyn WordFilter>,
+    ) -> Result<Solver, LbError> {
+        if CompiledDictionary::is_compiled_file(path) {
+            let dictionary = CompiledDictionary::open(path)?;
+            let words = dictionary.fitting(&puzzle);
+            return Ok(Solver::fit(puzzle, words, filter));
+        }
+        Solver::read(puzzle, File::open(path)?, filter)
+    }
+
+    // build a solver from any source of words, one per line, e.g. a file
+    // or a `&[u8]` of a word list that's already in memory. the lines are
+    // checked as they're read, into the same buffer, so only the words
+    // that fit the board are ever copied
+    pub fn new<R: Read>(puzzle: Puzzle, source: R) -> Result<Solver, LbError> {
+        Solver::read(puzzle, source, None)
+    }
+
+    fn read<R: Read>(
+        puzzle: Puzzle,
+        mut source: R,
+        filter: Option<&dyn WordFilter>,
+    ) -> Result<Solver, LbError> {
+        let mut start = Vec::new();
+        source
+            .by_ref()
+            .take(compiled::MAGIC.len() as u64)
+            .read_to_end(&mut start)?;
+        if CompiledDictionary::is_compiled(&start) {
+            source.read_to_end(&mut start)?;
+            let dictionary = CompiledDictionary::from_bytes(start)?;
+            let words = dictionary.fitting(&puzzle);
+            return Ok(Solver::fit(puzzle, words, filter));
+        }
+
+        let _span = log::Span::new(log::INFO, format_args!("filtering the word list"));
+        let mut fitting = FittingWords::new(&puzzle, filter);
+        let mut reader = io::BufReader::new(start.chain(source));
+        // read as bytes, so a line that isn't UTF-8 is skipped rather than
+        // stopping the whole list
+        let mut line = Vec::new();
+        let mut skipped = 0;
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            match std::str::from_utf8(&line) {
+                Ok(word) => {
+                    let word = word.strip_suffix('\n').unwrap_or(word);
+                    fitting.add(word.strip_suffix('\r').unwrap_or(word));
+                }
+                Err(_) => skipped += 1,
+            }
+            line.clear();
+        }
+        if skipped > 0 {
+            log::info!("skipped {} lines that aren't valid UTF-8", skipped);
+        }
+        let words = fitting.finish();
+        Ok(Solver::with_words(puzzle, words))
+    }
+
+    // build a solver from a word list that's already been read, so many
+    // boards can be solved without reading the file again
+    pub fn from_words<I, S>(puzzle: Puzzle, words: I) -> Solver
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Solver::fit(puzzle, words, None)
+    }
+
+    fn fit<I, S>(puzzle: Puzzle, words: I, filter: Option<&dyn WordFilter>) -> Solver
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let _span = log::Span::new(log::INFO, format_args!("filtering the word list"));
+        let mut fitting = FittingWords::new(&puzzle, filter);
+        for word in words {
+            fitting.add(word.as_ref());
+        }
+        let words = fitting.finish();
+        Solver::with_words(puzzle, words)
+    }
+
+    // the words are already the ones that fit the board, each once
+    fn with_words(puzzle: Puzzle, mut dictionary: Vec<String>) -> Solver {
+        // reorder dictionary by word length, longest first
+        dictionary.sort_by_key(|word| std::cmp::Reverse(word.chars().count()));
+        log::info!("{} words fit the board", dictionary.len());
+
+        let words = Arc::new(WordIndex::new(&puzzle, dictionary));
+        Solver {
+            puzzle,
+            words,
+            threads: 1,
+            required_words: Vec::new(),
+            start_word: None,
+            max_words: 6,
+            progress: None,
+            cost: None,
+            validator: None,
+            timeout: None,
+            max_nodes: None,
+            cancel: None,
+            limit: None,
+            all: false,
+            algorithm: Algorithm::AStar,
+            strategy: None,
+            heuristic: Heuristic::Letters,
+            weight: 1.0,
+            queue_limit: DEFAULT_QUEUE_LIMIT,
+            beam_width: DEFAULT_BEAM_WIDTH,
+            checkpoint: None,
+        }
+    }
+
+    // whether two dictionary entries are the same word, played the same
+    // way or not
+    pub(crate) fn is_same_word(&self, i: usize, j: usize) -> bool {
+        self.words.word_ids[i] == self.words.word_ids[j]
+    }
+
+    // the words that can come after a word in a chain
+    fn followers(&self, i: usize) -> &[usize] {
+        &self.words.followers[self.words.follower_ranges[i].clone()]
+    }
+
+    // drop words shorter than this from the dictionary, for solutions
+    // made of longer words
+    pub fn set_min_word_length(&mut self, min_word_length: usize) {
+        self.retain_words(|word| word.chars().count() >= min_word_length);
+    }
+
+    // only use the max_rank most common words in the frequency list, so
+    // the solutions are made of words people know
+    pub fn set_max_rank(&mut self, frequencies: &Frequencies, max_rank: usize) {
+        self.retain_words(|word| frequencies.is_common(word, max_rank));
+    }
+
+    // only use the words the callback keeps, e.g. the ones the game accepts
+    pub fn retain_words<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&str) -> bool,
+    {
+        // the indexes are built again rather than changed, since other
+        // solvers may be sharing them
+        let mut dictionary = self.words.dictionary.clone();
+        dictionary.retain(|word| keep(word));
+        self.words = Arc::new(WordIndex::new(&self.puzzle, dictionary));
+    }
+
+    pub fn puzzle(&self) -> &Puzzle {
+        &self.puzzle
+    }
+
+    // the words from the source that can be played on this board
+    pub fn dictionary(&self) -> &[String] {
+        &self.words.dictionary
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+    }
+
+    // search with this rather than the algorithm's own strategy, e.g. to
+    // try out a new one. run_solver still ranks what it finds
+    pub fn set_strategy<S>(&mut self, strategy: S)
+    where
+        S: SearchStrategy + 'static,
+    {
+        self.strategy = Some(CustomStrategy(Arc::new(strategy)));
+    }
+
+    pub fn set_heuristic(&mut self, heuristic: Heuristic) {
+        self.heuristic = heuristic;
+    }
+
+    // weighted A*: multiply the heuristic by this, so chains that cover
+    // more letters are tried sooner. 1 (the default) finds the solutions
+    // with the fewest words. above 1 the search stops going a depth at a
+    // time and heads straight for chains that nearly cover the board, which
+    // finds a solution much sooner on hard boards but not always the
+    // shortest one. only A* uses it
+    pub fn set_weight(&mut self, weight: f64) {
+        self.weight = if weight.is_finite() {
+            weight.max(1.0)
+        } else {
+            1.0
+        };
+    }
+
+    // how many chains Sma keeps at most, the ones it forgets go first
+    pub fn set_queue_limit(&mut self, queue_limit: usize) {
+        self.queue_limit = queue_limit.max(1);
+    }
+
+    // how many chains Beam keeps at each length. wider beams are less
+    // likely to miss the best solution, and take longer
+    pub fn set_beam_width(&mut self, beam_width: usize) {
+        self.beam_width = beam_width.max(1);
+    }
+
+    // the A* estimate for a chain of this many words with this heuristic
+    fn estimate(&self, cost: usize, heuristic: usize) -> u64 {
+        ((cost as f64 + self.weight * heuristic as f64) * 1000.0).round() as u64
+    }
+
+    // how many threads to split the search across, 1 searches on this thread
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    // only return solutions that include all of these words
+    pub fn set_required_words(&mut self, words: &[String]) {
+        self.required_words = words.to_vec();
+    }
+
+    // only return solutions that start with this word, for finishing
+    // off a game that's already been started
+    pub fn set_start_word(&mut self, word: Option<String>) {
+        self.start_word = word;
+    }
+
+    // give up rather than look for solutions with more words than this
+    pub fn set_max_words(&mut self, max_words: usize) {
+        self.max_words = max_words.max(1);
+    }
+
+    // call this every so often while searching, and whenever the search
+    // moves on to longer chains. with more than one thread, each thread
+    // reports its own progress
+    pub fn set_progress<F>(&mut self, callback: F)
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        self.progress = Some(ProgressCallback(Arc::new(callback)));
+    }
+
+    // score chains with the same number of words, e.g. fewer letters or
+    // more common words, lower being better. the search tries cheaper
+    // chains first, and run_solver looks at every solution with the fewest
+    // words and returns the cheapest of them, so it takes longer on boards
+    // with lots of solutions
+    pub fn set_cost<F>(&mut self, cost: F)
+    where
+        F: Fn(&[&str]) -> u64 + Send + Sync + 'static,
+    {
+        self.cost = Some(CostFunction(Arc::new(cost)));
+    }
+
+    // called with each word as the search is about to put it on the end of
+    // a chain, and the words in the chain before it, and the word is left
+    // out there if it returns false. it's asked again for every chain, so
+    // it can depend on the words before, and can change its mind between
+    // searches (e.g. as words are played in the real game) without the
+    // word list being filtered again
+    pub fn set_validator<F>(&mut self, validator: F)
+    where
+        F: Fn(&str, &[&str]) -> bool + Send + Sync + 'static,
+    {
+        self.validator = Some(WordValidator(Arc::new(validator)));
+    }
+
+    // whether the validator lets the word follow the chain, always when
+    // there isn't one
+    pub(crate) fn allows<I>(&self, chain: I, i: usize) -> bool
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        match &self.validator {
+            Some(WordValidator(validator)) => {
+                let words: Vec<&str> = chain
+                    .into_iter()
+                    .map(|j| self.words.dictionary[j].as_str())
+                    .collect();
+                validator(&self.words.dictionary[i], &words)
+            }
+            None => true,
+        }
+    }
+
+    // what the cost function makes of a chain of dictionary words
+    fn chain_cost(&self, chain: &[usize]) -> u64 {
+        match &self.cost {
+            Some(CostFunction(cost)) => {
+                let words: Vec<&str> = chain
+                    .iter()
+                    .map(|&i| self.words.dictionary[i].as_str())
+                    .collect();
+                cost(&words)
+            }
+            None => 0,
+        }
+    }
+
+    // how many solutions run_solver returns, by default 4 when the best
+    // solutions have 3 words or fewer and just the first one for longer
+    // chains, where looking for more takes a long time
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit.map(|limit| limit.max(1));
+    }
+
+    // how many solutions with this many words to return
+    pub fn solution_limit(&self, word_count: usize) -> usize {
+        if self.all {
+            return usize::MAX;
+        }
+        match self.limit {
+            Some(limit) => limit,
+            None if word_count <= 3 => 4,
+            None => 1,
+        }
+    }
+
+    // how many solutions with this many words to find. all of them when
+    // there's a cost function, so the cheapest can be picked out
+    fn search_limit(&self, word_count: usize) -> usize {
+        match self.cost {
+            Some(_) => usize::MAX,
+            None => self.solution_limit(word_count),
+        }
+    }
+
+    // the cheapest of the solutions found, up to the limit
+    fn rank(&self, mut found: Vec<Solution>) -> Vec<Solution> {
+        let (cost, word_count) = match (&self.cost, found.first()) {
+            (Some(CostFunction(cost)), Some(solution)) => (cost, solution.word_count()),
+            _ => return found,
+        };
+        found.sort_by_cached_key(|solution| {
+            let words: Vec<&str> = solution.words.iter().map(String::as_str).collect();
+            cost(&words)
+        });
+        found.truncate(self.solution_limit(word_count));
+        found
+    }
+
+    // have run_solver return every solution with the fewest words there
+    // can be, rather than the first few it comes across
+    pub fn set_all(&mut self, all: bool) {
+        self.all = all;
+    }
+
+    // stop each search after this long, with whatever it's found by then
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    // when a search starting now has to stop by, none for a timeout too
+    // long to count to, which is as good as none
+    fn deadline(&self) -> Option<Instant> {
+        self.timeout
+            .and_then(|timeout| Instant::now().checked_add(timeout))
+    }
+
+    // stop each search after expanding this many chains, with whatever
+    // it's found by then. a search split across threads gives each an
+    // even share
+    pub fn set_max_nodes(&mut self, max_nodes: Option<usize>) {
+        self.max_nodes = max_nodes;
+    }
+
+    // stop searching once the token is cancelled, with whatever's been
+    // found by then
+    pub fn set_cancel_token(&mut self, token: Option<CancelToken>) {
+        self.cancel = token;
+    }
+
+    // have the search save its state to this file every so often, and when
+    // it's stopped early, so `resume_solver` can carry on from there. only
+    // the A* search on one thread saves its state
+    pub fn set_checkpoint(&mut self, path: Option<PathBuf>) {
+        self.checkpoint = path;
+    }
+
+    // the dictionary indexes the search can start from
+    fn first_words(&self) -> Vec<usize> {
+        match &self.start_word {
+            Some(start_word) => (0..self.words.dictionary.len())
+                .filter(|&i| self.words.dictionary[i] == *start_word)
+                .collect(),
+            None => (0..self.words.dictionary.len()).collect(),
+        }
+    }
+
+    // this is the solver part of the program
+    pub fn run_solver(&self, ignore_words: &[String]) -> Result<Vec<Solution>, LbError> {
+        self.run_solver_with_stats(ignore_words).0
+    }
+
+    // the same as run_solver, along with what the search did
+    pub fn run_solver_with_stats(
+        &self,
+        ignore_words: &[String],
+    ) -> (Result<Vec<Solution>, LbError>, SearchStats) {
+        let _span = log::Span::new(
+            log::INFO,
+            format_args!("searching for up to {} words", self.max_words),
+        );
+        let started = Instant::now();
+        let deadline = self.deadline();
+        let SearchResult {
+            solutions: found,
+            mut stats,
+        } = match &self.strategy {
+            Some(CustomStrategy(strategy)) => strategy.solve(self, ignore_words),
+            None => self.algorithm.strategy().solve(self, ignore_words),
+        };
+        stats.search_time = started.elapsed();
+        self.finish(found, deadline, stats)
+    }
+
+    // carry on the search saved in a checkpoint by `set_checkpoint`, which
+    // has to be for the same board, words and ignore words
+    pub fn resume_solver<P: AsRef<Path>>(
+        &self,
+        path: P,
+        ignore_words: &[String],
+    ) -> Result<Vec<Solution>, LbError> {
+        self.resume_solver_with_stats(path, ignore_words).0
+    }
+
+    // the same as resume_solver, along with what the search did after
+    // carrying on
+    pub fn resume_solver_with_stats<P: AsRef<Path>>(
+        &self,
+        path: P,
+        ignore_words: &[String],
+    ) -> (Result<Vec<Solution>, LbError>, SearchStats) {
+        let started = Instant::now();
+        let deadline = self.deadline();
+        let mut solutions = self.search(ignore_words, [], 0);
+        if let Err(err) = solutions.load_checkpoint(path.as_ref()) {
+            return (Err(err), SearchStats::default());
+        }
+        // the expansions allowed are on top of the ones already done
+        solutions.max_nodes = self
+            .max_nodes
+            .map(|max_nodes| solutions.nodes_expanded.saturating_add(max_nodes));
+        log::info!(
+            "resuming the search at {} words, {} chains to carry on from",
+            solutions.depth,
+            solutions.priority_queue.len() + solutions.deferred.len()
+        );
+        let found = Solver::best_solutions(&mut solutions);
+        let stats = SearchStats {
+            search_time: started.elapsed(),
+            ..solutions.stats()
+        };
+        self.finish(found, deadline, stats)
+    }
+
+    // what run_solver returns for the solutions a search found
+    fn finish(
+        &self,
+        found: Vec<Solution>,
+        deadline: Option<Instant>,
+        mut stats: SearchStats,
+    ) -> (Result<Vec<Solution>, LbError>, SearchStats) {
+        let stopped = stats.stopped
+            || self.cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+            || deadline.is_some_and(|deadline| Instant::now() >= deadline);
+        if found.is_empty() && stopped {
+            return (Err(LbError::Timeout), stats);
+        }
+        let started = Instant::now();
+        let ranked = self.rank(self.distinct(found));
+        stats.ranking_time = started.elapsed();
+        (Ok(ranked), stats)
+    }
+
+    // take the first solution, then a few more with the same number of words
+    fn best_solutions(solutions: &mut Solutions) -> Vec<Solution> {
+        if solutions.ida.as_ref().is_some_and(|ida| !ida.deepen) {
+            return Solver::best_dfs_solutions(solutions);
+        }
+        // a resumed search can have found some already
+        let mut found = solutions.found_solutions();
+        if found.is_empty() {
+            let first = match solutions.next() {
+                Some(first) => first,
+                None => return Vec::new(),
+            };
+
+            // only look for more solutions with the same number of words
+            let word_count = first.word_count();
+            solutions.set_max_words(word_count);
+            if let Some(bound) = solutions.bound {
+                bound.fetch_min(word_count, AtomicOrdering::Relaxed);
+            }
+            found.push(first);
+        }
+        let limit = solutions.solver.search_limit(found[0].word_count());
+        found.extend(solutions.take(limit.saturating_sub(found.len())));
+        found
+    }
+
+    // a depth first search can find a long solution before a short one, so
+    // it keeps going until every chain as short as the best so far has
+    // been looked at
+    fn best_dfs_solutions(solutions: &mut Solutions) -> Vec<Solution> {
+        let mut found: Vec<Solution> = Vec::new();
+        while let Some(solution) = solutions.next() {
+            let word_count = solution.word_count();
+            if found
+                .first()
+                .is_none_or(|best| word_count < best.word_count())
+            {
+                found.clear();
+                solutions.set_max_words(word_count);
+                if let Some(bound) = solutions.bound {
+                    bound.fetch_min(word_count, AtomicOrdering::Relaxed);
+                }
+            }
+            if found.len() < solutions.solver.search_limit(word_count) {
+                found.push(solution);
+            }
+        }
+        found
+    }
+
+    // split the first words up by their start letter and search each share
+    // on its own thread. the fewest words any thread has needed so far is
+    // shared, so the others give up on longer chains
+    fn run_parallel(&self, ignore_words: &[String]) -> (Vec<Solution>, SearchStats) {
+        log::info!("splitting the search across {} threads", self.threads);
+
+        let mut is_first_word = vec![false; self.words.dictionary.len()];
+        for i in self.first_words() {
+            is_first_word[i] = true;
+        }
+        let mut shares: Vec<Vec<usize>> = vec![Vec::new(); self.threads];
+        for (i, words) in self.words.start_letter_dictionary.values().enumerate() {
+            shares[i % self.threads].extend(words.iter().filter(|&&i| is_first_word[i]));
+        }
+
+        let bound = AtomicUsize::new(usize::MAX);
+        let mut stats = SearchStats::default();
+        let mut found: Vec<Solution> = thread::scope(|scope| {
+            let handles: Vec<_> = shares
+                .into_iter()
+                .map(|first_words| {
+                    let bound = &bound;
+                    scope.spawn(move || {
+                        let mut solutions = self.search(ignore_words, first_words, 0);
+                        solutions.bound = Some(bound);
+                        solutions.max_nodes = self
+                            .max_nodes
+                            .map(|max_nodes| max_nodes.div_ceil(self.threads));
+                        (Solver::best_solutions(&mut solutions), solutions.stats())
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| {
+                    let (found, thread_stats) = handle.join().unwrap();
+                    stats.add(&thread_stats);
+                    found
+                })
+                .collect()
+        });
+
+        let best = bound.load(AtomicOrdering::Relaxed);
+        found.retain(|solution| solution.word_count() == best);
+        found.truncate(self.search_limit(best));
+        (found, stats)
+    }
+
+    // every two word solution for the board: join the words ending in each
+    // letter with the words starting with it, and keep the pairs that
+    // cover the whole board between them
+    pub fn pairs(&self, ignore_words: &[String]) -> Vec<Solution> {
+        if self.max_words < 2 {
+            return Vec::new();
+        }
+        let words = match self.join_words(ignore_words) {
+            Some(words) => words,
+            None => return Vec::new(),
+        };
+
+        let pairs = self
+            .join_pairs(&words, usize::MAX)
+            .iter()
+            .map(|pair| {
+                Solution::new(
+                    pair.iter()
+                        .map(|&i| self.words.dictionary[i].clone())
+                        .collect(),
+                )
+            })
+            .collect();
+        self.distinct(pairs)
+    }
+
+    // the same words played another way are the same solution
+    fn distinct(&self, mut found: Vec<Solution>) -> Vec<Solution> {
+        if self.puzzle.has_duplicates() {
+            let mut seen = HashSet::new();
+            found.retain(|solution| seen.insert(solution.words.clone()));
+        }
+        found
+    }
+
+    // every solution for the board, shortest chains first, found lazily
+    // as the A* search reaches them so callers can stop whenever they like
+    pub fn solutions(&self, ignore_words: &[String]) -> Solutions<'_> {
+        self.search(ignore_words, self.first_words(), 0)
+    }
+
+    // the ways to finish a game where these words have already been played,
+    // as chains of the words still to play. the played words can't be used
+    // again, and the letters they cover already count
+    pub fn solutions_after(&self, played: &[String], ignore_words: &[String]) -> Solutions<'_> {
+        let mut ignore_words = ignore_words.to_vec();
+        ignore_words.extend(played.iter().cloned());
+        // played the way that covers the most, when there's a choice
+        let played_words: Vec<&str> = played.iter().map(String::as_str).collect();
+        let path = self.puzzle.best_path(&played_words);
+        let coverage = match path {
+            Some(path) => path.mask,
+            None => self.puzzle.chain_mask(played),
+        };
+        let last_letter = match path {
+            Some(path) => Some(self.puzzle.tile_key(path.last)),
+            None => played.last().and_then(|word| word.chars().last()),
+        };
+        let first_words = match last_letter {
+            Some(letter) => self
+                .words
+                .start_letter_dictionary
+                .get(&letter)
+                .cloned()
+                .unwrap_or_default(),
+            None => self.first_words(),
+        };
+        self.search(&ignore_words, first_words, coverage)
+    }
+
+    // rate how hard the board is from how many words fit it, how many two
+    // word solutions it has and how evenly the words cover its letters
+    pub fn difficulty(&self) -> Difficulty {
+        // a word that can be played more than one way is still one word
+        let word_count = (0..self.words.dictionary.len())
+            .filter(|&i| self.words.word_ids[i] == i)
+            .count();
+        let two_word_solutions = self.pairs(&[]).len();
+
+        // how many words use each letter
+        let letter_words: Vec<(char, usize)> = self
+            .puzzle
+            .available_chars
+            .iter()
+            .map(|&c| {
+                let bit = self.puzzle.letter_bits[&c];
+                let count = self
+                    .words
+                    .word_masks
+                    .iter()
+                    .filter(|&&mask| mask & bit != 0)
+                    .count();
+                (c, count)
+            })
+            .collect();
+        let total: usize = letter_words.iter().map(|&(_, count)| count).sum();
+        let entropy: f64 = letter_words
+            .iter()
+            .filter(|&&(_, count)| count > 0)
+            .map(|&(_, count)| {
+                let p = count as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum();
+        let letter_entropy = if letter_words.len() > 1 && total > 0 {
+            entropy / (letter_words.len() as f64).log2()
+        } else {
+            0.0
+        };
+        let rarest_letter = letter_words.iter().copied().min_by_key(|&(_, count)| count);
+
+        // lots of pairs, lots of words and evenly used letters all make a
+        // board easier, the pairs most of all
+        let ease = 3.0 * (two_word_solutions as f64 + 1.0).log10()
+            + (word_count as f64 + 1.0).log10()
+            + 2.0 * letter_entropy;
+        let rating = (10.0 - 0.6 * ease).clamp(1.0, 10.0);
+
+        Difficulty {
+            rating,
+            word_count,
+            two_word_solutions,
+            letter_entropy,
+            rarest_letter,
+        }
+    }
+
+    // the shortest way to finish from here, its first word is the one to
+    // play next. none if there's no way to finish
+    pub fn hint(&self, played: &[String], ignore_words: &[String]) -> Option<Solution> {
+        self.solutions_after(played, ignore_words).next()
+    }
+
+    // start a search from the given first words, with the letters in
+    // `coverage` already covered
+    fn search<I>(&self, ignore_words: &[String], first_words: I, coverage: u64) -> Solutions<'_>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        // look up the ignore words once rather than on every expansion
+        let ignored: Vec<bool> = self
+            .words
+            .dictionary
+            .iter()
+            .map(|word| ignore_words.contains(word))
+            .collect();
+
+        // a required word that can't be played on this board means
+        // there are no solutions at all
+        let required_indexes: Option<BTreeSet<usize>> = self
+            .required_words
+            .iter()
+            .map(|word| self.words.dictionary.iter().position(|w| w == word))
+            .collect();
+        // every copy of a required word counts
+        let required: Vec<bool> = self
+            .words
+            .dictionary
+            .iter()
+            .map(|word| self.required_words.contains(word))
+            .collect();
+        let required_count = required_indexes.as_ref().map_or(0, BTreeSet::len);
+
+        let mut solutions = Solutions {
+            solver: self,
+            ignored,
+            required,
+            priority_queue: BinaryHeap::new(),
+            deferred: Vec::new(),
+            ida: None,
+            depth: 1,
+            max_words: self.max_words,
+            bound: None,
+            nodes_expanded: 0,
+            nodes_pruned: 0,
+            peak_queue_size: 0,
+            best_coverage: 0,
+            deadline: self.deadline(),
+            max_nodes: self.max_nodes,
+            stopped: false,
+            found: Vec::new(),
+            next_checkpoint: self
+                .checkpoint
+                .as_ref()
+                .map(|_| Instant::now() + CHECKPOINT_INTERVAL),
+        };
+        if required_indexes.is_none() {
+            return solutions;
+        }
+
+        if matches!(self.algorithm, Algorithm::Ida | Algorithm::Dfs) {
+            let deepen = self.algorithm == Algorithm::Ida;
+            if !deepen {
+                solutions.depth = solutions.max_words;
+            }
+            solutions.ida = Some(Ida {
+                first_words: first_words.into_iter().collect(),
+                next_first: 0,
+                coverage,
+                required_count,
+                deepen,
+                stack: Vec::new(),
+            });
+            return solutions;
+        }
+        // weighted, chains of every length are searched best first at once
+        if self.weight > 1.0 {
+            solutions.depth = solutions.max_words;
+        }
+
+        for i in first_words {
+            // ignore this word if it's in the ignore_words list
+            if solutions.ignored[i] || !self.allows([], i) {
+                solutions.nodes_pruned += 1;
+                continue;
+            }
+            let coverage = coverage | self.words.word_masks[i];
+            let missing_required = required_count - usize::from(solutions.required[i]);
+            let heuristic = self.heuristic(coverage, missing_required, i);
+            if 1 + heuristic > solutions.max_words {
+                solutions.nodes_pruned += 1;
+                continue;
+            }
+            solutions.priority_queue.push(State {
+                chain: vec![i],
+                used_words: word_bit(self.words.word_ids[i]),
+                coverage,
+                missing_required,
+                cost: 1,
+                heuristic,
+                estimate: self.estimate(1, heuristic),
+                score: self.chain_cost(&[i]),
+            });
+        }
+        solutions.peak_queue_size = solutions.priority_queue.len();
+        solutions
+    }
+
+    // useful for A* search, the fewest more words a chain ending in `last`
+    // could be finished in. the next word can add at most the most new
+    // letters any of last's followers can, and each word after it at most
+    // the most any word can, and every missing required word takes a word
+    // of its own. one or two more words are only enough when the covering
+    // tables say they can cover every missing letter. it's 0 only for a
+    // chain that's a solution
+    fn heuristic(&self, coverage: u64, missing_required: usize, last: usize) -> usize {
+        let missing = self.puzzle.full_mask() & !coverage;
+        let missing_letters = missing.count_ones() as usize;
+        let next_cover = self.words.follower_cover[last];
+        let mut words_needed = if missing_letters == 0 {
+            0
+        } else if missing_letters <= next_cover {
+            1
+        } else {
+            1 + (missing_letters - next_cover).div_ceil(self.words.max_cover.max(1))
+        };
+        if words_needed == 1 && !self.can_finish(last, missing, 1) {
+            words_needed = 2;
+        }
+        if words_needed == 2 && !self.can_finish(last, missing, 2) {
+            words_needed = 3;
+        }
+        if self.heuristic == Heuristic::Cover && words_needed > 0 {
+            words_needed = words_needed.max(self.cover_bound(missing));
+        }
+        words_needed.max(missing_required)
+    }
+
+    // whether one or two words that can follow `last` cover all the
+    // missing letters, taken to be so when the board's too big for the
+    // tables to say
+    fn can_finish(&self, last: usize, missing: u64, words: usize) -> bool {
+        let letter = self.words.word_ends[last].1;
+        self.words.covering.as_ref().is_none_or(|covering| {
+            if words == 1 {
+                covering.any(letter, missing)
+            } else {
+                covering.any_pair(letter, missing)
+            }
+        })
+    }
+
+    // how many of the missing places can be picked with no two of them in
+    // the same word, each of which needs a word of its own
+    fn cover_bound(&self, missing: u64) -> usize {
+        let mut blocked = 0;
+        let mut count = 0;
+        for &place in &self.words.cover_order {
+            let bit = 1 << place;
+            if missing & bit != 0 && blocked & bit == 0 {
+                count += 1;
+                blocked |= bit | self.words.together[place as usize];
+            }
+        }
+        count
+    }
+}
+
+// how hard a board is, with the numbers the rating comes from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difficulty {
+    // from 1 (easy) to 10 (hard)
+    pub rating: f64,
+    // how many dictionary words fit the board
+    pub word_count: usize,
+    // how many two word solutions there are
+    pub two_word_solutions: usize,
+    // how evenly the words share the letters out, from 0 when every word
+    // uses the same letters to 1 when each letter is in as many words as
+    // any other. boards with a letter hardly any words use are harder
+    pub letter_entropy: f64,
+    // the letter the fewest words use, and how many do
+    pub rarest_letter: Option<(char, usize)>,
+}
+
+impl Difficulty {
+    pub fn label(&self) -> &'static str {
+        if self.rating < 4.0 {
+            "easy"
+        } else if self.rating < 7.0 {
+            "medium"
+        } else {
+            "hard"
+        }
+    }
+
+    pub fn to_json(&self) -> json::Value {
+        json::Value::object()
+            .with("rating", (self.rating * 10.0).round() / 10.0)
+            .with("label", self.label())
+            .with("word_count", self.word_count)
+            .with("two_word_solutions", self.two_word_solutions)
+            .with(
+                "letter_entropy",
+                (self.letter_entropy * 100.0).round() / 100.0,
+            )
+            .with(
+                "rarest_letter",
+                self.rarest_letter.map(|(letter, _)| letter),
+            )
+            .with(
+                "rarest_letter_words",
+                self.rarest_letter.map(|(_, count)| count),
+            )
+    }
+}
+
+// drop repeated solutions, keeping the first of each. with `ignore_order`
+// chains of the same words in a different order count as repeats too
+pub fn dedupe_solutions(solutions: &mut Vec<Solution>, ignore_order: bool) {
+    let mut seen: HashSet<Vec<String>> = HashSet::new();
+    solutions.retain(|solution| {
+        if ignore_order {
+            seen.insert(solution.sorted_words())
+        } else {
+            seen.insert(solution.words.clone())
+        }
+    });
+}
+
+// the options for solving a board in one go, for callers that don't want
+// to set up a solver themselves, e.g. the wasm bindings
+#[derive(Debug, Clone)]
+pub struct SolveOptions {
+    pub ignore_words: Vec<String>,
+    pub required_words: Vec<String>,
+    pub start_word: Option<String>,
+    pub max_words: usize,
+    pub min_word_length: usize,
+    // how many solutions to return, none for the solver's default
+    pub limit: Option<usize>,
+    // how many chains the search can expand, none for no limit
+    pub max_nodes: Option<usize>,
+    // list every two word solution instead of searching
+    pub pairs: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            ignore_words: Vec::new(),
+            required_words: Vec::new(),
+            start_word: None,
+            max_words: 6,
+            min_word_length: 3,
+            limit: None,
+            max_nodes: None,
+            pairs: false,
+        }
+    }
+}
+
+impl SolveOptions {
+    // read the options from a JSON object like {"ignore": ["word"],
+    // "max_words": 3}, anything that's missing keeps its default
+    pub fn from_json(value: &json::Value) -> SolveOptions {
+        let defaults = SolveOptions::default();
+        let strings = |key| value.get(key).and_then(json::Value::as_strings);
+        let number = |key| value.get(key).and_then(json::Value::as_f64);
+        SolveOptions {
+            ignore_words: strings("ignore").unwrap_or_default(),
+            required_words: strings("require").unwrap_or_default(),
+            start_word: value
+                .get("start_with")
+                .and_then(json::Value::as_str)
+                .map(str::to_string),
+            max_words: number("max_words").map_or(defaults.max_words, |n| n as usize),
+            min_word_length: number("min_word_length")
+                .map_or(defaults.min_word_length, |n| n as usize),
+            limit: number("limit").map(|n| n as usize),
+            max_nodes: number("max_nodes").map(|n| n as usize),
+            pairs: value
+                .get("pairs")
+                .and_then(json::Value::as_bool)
+                .unwrap_or(defaults.pairs),
+        }
+    }
+}
+
+// solve a board with the given word list
+pub fn solve<S: AsRef<str>>(
+    board: &[String],
+    words: &[S],
+    options: &SolveOptions,
+) -> Result<Vec<Solution>, LbError> {
+    solve_with(Solver::from_words(Puzzle::new(board)?, words), options)
+}
+
+fn solve_with(mut solver: Solver, options: &SolveOptions) -> Result<Vec<Solution>, LbError> {
+    solver.set_required_words(&options.required_words);
+    solver.set_start_word(options.start_word.clone());
+    solver.set_max_words(options.max_words);
+    solver.set_min_word_length(options.min_word_length);
+    solver.set_limit(options.limit);
+    solver.set_max_nodes(options.max_nodes);
+    if options.pairs {
+        return Ok(solver.pairs(&options.ignore_words));
+    }
+    solver.run_solver(&options.ignore_words)
+}
+
+// the bit a dictionary index sets in a chain's used words
+fn word_bit(i: usize) -> u64 {
+    1 << (i % 64)
+}
+
+// the A* search behind `Solver::solutions`
+//
+// it finds solutions with 1 word, then 2, then 3 etc, so the shortest
+// solutions always come out first. chains that are longer than the
+// current depth are put to one side until that depth has been searched
+pub struct Solutions<'a> {
+    solver: &'a Solver,
+    // whether each dictionary word is in the ignore list
+    ignored: Vec<bool>,
+    // whether each dictionary word is one of the required words
+    required: Vec<bool>,
+    priority_queue: BinaryHeap<State>,
+    deferred: Vec<State>,
+    // the depth first search's state, when it's searching with IDA* or
+    // plain depth first rather than the queue
+    ida: Option<Ida>,
+    depth: usize,
+    max_words: usize,
+    // the fewest words a parallel search has needed so far
+    bound: Option<&'a AtomicUsize>,
+    nodes_expanded: usize,
+    nodes_pruned: usize,
+    peak_queue_size: usize,
+    best_coverage: usize,
+    // when the search gives up, and after how many expansions
+    deadline: Option<Instant>,
+    max_nodes: Option<usize>,
+    // whether it stopped early, from the timeout, the cancel token or
+    // running out of expansions
+    stopped: bool,
+    // the chains it's returned so far, and when it next saves its state,
+    // for the checkpoint
+    found: Vec<Vec<usize>>,
+    next_checkpoint: Option<Instant>,
+}
+
+impl Solutions<'_> {
+    // another thread has already found a shorter solution
+    fn is_beaten(&self) -> bool {
+        self.bound
+            .is_some_and(|bound| self.depth > bound.load(AtomicOrdering::Relaxed))
+    }
+
+    // check whether the search has run out of time or been cancelled
+    fn should_stop(&mut self) -> bool {
+        let cancelled = self
+            .solver
+            .cancel
+            .as_ref()
+            .is_some_and(CancelToken::is_cancelled);
+        let timed_out = self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+        let out_of_nodes = self
+            .max_nodes
+            .is_some_and(|max_nodes| self.nodes_expanded >= max_nodes);
+        if (cancelled || timed_out || out_of_nodes) && !self.stopped {
+            log::info!("stopping the search early at depth {}", self.depth);
+            self.stopped = true;
+        }
+        self.stopped
+    }
+
+    // whether the search stopped before it was finished, so there may
+    // be solutions it didn't get to
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    // stop searching once every chain of this many words has been tried
+    pub fn set_max_words(&mut self, max_words: usize) {
+        self.max_words = max_words;
+        self.depth = self.depth.min(max_words);
+    }
+
+    // the number of words in the chains currently being searched
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            nodes_expanded: self.nodes_expanded,
+            depth: self.depth,
+            queue_size: self.priority_queue.len()
+                + self.deferred.len()
+                + self.ida.as_ref().map_or(0, |ida| ida.stack.len()),
+            best_coverage: self.best_coverage,
+            letter_count: self.solver.puzzle.full_mask().count_ones() as usize,
+        }
+    }
+
+    // what the search has done so far, without the times
+    pub fn stats(&self) -> SearchStats {
+        SearchStats {
+            nodes_expanded: self.nodes_expanded,
+            nodes_pruned: self.nodes_pruned,
+            peak_queue_size: self.peak_queue_size,
+            stopped: self.stopped,
+            ..SearchStats::default()
+        }
+    }
+
+    fn report_progress(&self, progress: Progress) {
+        if let Some(ProgressCallback(callback)) = &self.solver.progress {
+            callback(&progress);
+        }
+    }
+
+    // the next solution from the depth first search
+    fn next_ida(&mut self) -> Option<Solution> {
+        // taken out while it's searched so the rest of self can be used
+        let mut ida = self.ida.take()?;
+        let solution = self.search_ida(&mut ida);
+        self.ida = Some(ida);
+        solution
+    }
+
+    fn search_ida(&mut self, ida: &mut Ida) -> Option<Solution> {
+        let solver = self.solver;
+        loop {
+            // without deepening, a shorter chain found by another thread
+            // only shortens the chains left to look at
+            if let (false, Some(bound)) = (ida.deepen, self.bound) {
+                self.depth = self.depth.min(bound.load(AtomicOrdering::Relaxed));
+            }
+            if self.is_beaten() || self.should_stop() {
+                return None;
+            }
+
+            let length = ida.stack.len();
+            let top = match ida.stack.last() {
+                Some(top) => top,
+                None => {
+                    // start from the next first word
+                    if let Some(&i) = ida.first_words.get(ida.next_first) {
+                        ida.next_first += 1;
+                        if self.ignored[i] || !solver.allows([], i) {
+                            self.nodes_pruned += 1;
+                            continue;
+                        }
+                        let frame =
+                            ida.frame(solver, i, ida.coverage, ida.required_count, &self.required);
+                        if ida.fits(solver, &frame, 1, self.depth) {
+                            ida.stack.push(frame);
+                            self.peak_queue_size = self.peak_queue_size.max(1);
+                        } else {
+                            self.nodes_pruned += 1;
+                        }
+                        continue;
+                    }
+                    // every chain up to this depth has been tried, so start
+                    // again a word deeper
+                    if !ida.deepen || self.depth >= self.max_words {
+                        return None;
+                    }
+                    self.depth += 1;
+                    ida.next_first = 0;
+                    log::info!("searching chains of up to {} words", self.depth);
+                    self.report_progress(self.progress());
+                    continue;
+                }
+            };
+
+            if solver.heuristic(top.coverage, top.missing_required, top.word) == 0 {
+                // when deepening, chains that finish before this depth
+                // were found at an earlier one
+                let found = if ida.deepen {
+                    length == self.depth
+                } else {
+                    length <= self.depth
+                };
+                let words: Vec<String> = ida
+                    .stack
+                    .iter()
+                    .map(|frame| solver.words.dictionary[frame.word].clone())
+                    .collect();
+                ida.stack.pop();
+                if found {
+                    let solution = Solution::new(words);
+                    log::debug!(
+                        "found {:?} after expanding {} chains",
+                        solution.words,
+                        self.nodes_expanded
+                    );
+                    return Some(solution);
+                }
+                continue;
+            }
+            if length >= self.depth {
+                ida.stack.pop();
+                self.nodes_pruned += 1;
+                continue;
+            }
+
+            // the next word that can follow the last one and still finish
+            // in time, if there's one left
+            let (coverage, missing_required) = (top.coverage, top.missing_required);
+            let next_words = solver.followers(top.word);
+            let mut position = top.next;
+            let mut child = None;
+            while let Some(&i) = next_words.get(position) {
+                position += 1;
+                if self.ignored[i]
+                    || ida
+                        .stack
+                        .iter()
+                        .any(|frame| solver.is_same_word(frame.word, i))
+                    || !solver.allows(ida.stack.iter().map(|frame| frame.word), i)
+                {
+                    self.nodes_pruned += 1;
+                    continue;
+                }
+                let frame = ida.frame(solver, i, coverage, missing_required, &self.required);
+                if ida.fits(solver, &frame, length + 1, self.depth) {
+                    child = Some(frame);
+                    break;
+                }
+                self.nodes_pruned += 1;
+            }
+            ida.stack[length - 1].next = position;
+
+            match child {
+                Some(frame) => {
+                    self.best_coverage =
+                        self.best_coverage.max(frame.coverage.count_ones() as usize);
+                    ida.stack.push(frame);
+                    self.peak_queue_size = self.peak_queue_size.max(ida.stack.len());
+                    self.nodes_expanded += 1;
+                    if self.nodes_expanded.is_multiple_of(PROGRESS_INTERVAL) {
+                        self.report_progress(Progress {
+                            queue_size: ida.stack.len(),
+                            ..self.progress()
+                        });
+                    }
+                }
+                None => {
+                    ida.stack.pop();
+                }
+            }
+        }
+    }
+
+    // cut the queue back to three quarters of Sma's limit, so it isn't
+    // done again on the very next expansion, keeping the chains with the
+    // best estimates. the ones put to one side for the next depth go back
+    // in the queue, which puts them to one side again when they come up
+    fn forget_worst(&mut self) {
+        let keep = (self.solver.queue_limit / 4 * 3).max(1);
+        let mut states = mem::take(&mut self.priority_queue).into_vec();
+        states.append(&mut self.deferred);
+        // best first, State orders better chains higher
+        states.select_nth_unstable_by(keep, |a, b| b.cmp(a));
+        log::info!(
+            "the queue is full, forgetting {} chains",
+            states.len() - keep
+        );
+        self.nodes_pruned += states.len() - keep;
+        states.truncate(keep);
+        self.priority_queue = states.into();
+    }
+
+    fn expand(&mut self, state: &State) {
+        let solver = self.solver;
+        let last = *state.chain.last().unwrap();
+
+        for &i in solver.followers(last) {
+            // don't add the word if it's already in the chain
+            let bit = word_bit(solver.words.word_ids[i]);
+            if state.used_words & bit != 0 && state.chain.iter().any(|&j| solver.is_same_word(i, j))
+            {
+                self.nodes_pruned += 1;
+                continue;
+            }
+            // if the word is in the ignore_words list, or the validator
+            // turns it down, skip it
+            if self.ignored[i] || !solver.allows(state.chain.iter().copied(), i) {
+                self.nodes_pruned += 1;
+                continue;
+            }
+
+            // the parent's coverage and missing words only change by
+            // what this word adds
+            let coverage = state.coverage | solver.words.word_masks[i];
+            let missing_required = state.missing_required - usize::from(self.required[i]);
+            let h = solver.heuristic(coverage, missing_required, i);
+            // no point keeping a chain that can't be finished in time
+            if state.cost + 1 + h > self.max_words {
+                self.nodes_pruned += 1;
+                continue;
+            }
+
+            let mut new_chain = state.chain.clone();
+            new_chain.push(i);
+            let score = solver.chain_cost(&new_chain);
+
+            self.priority_queue.push(State {
+                chain: new_chain,
+                used_words: state.used_words | bit,
+                coverage,
+                missing_required,
+                cost: state.cost + 1,
+                heuristic: h,
+                estimate: solver.estimate(state.cost + 1, h),
+                score,
+            });
+        }
+    }
+}
+
+impl Iterator for Solutions<'_> {
+    type Item = Solution;
+
+    fn next(&mut self) -> Option<Solution> {
+        if self.ida.is_some() {
+            return self.next_ida();
+        }
+        loop {
+            while let Some(state) = self.priority_queue.pop() {
+                if self.is_beaten() {
+                    return None;
+                }
+                if self.should_stop() {
+                    // put back, so the checkpoint carries on from here
+                    self.priority_queue.push(state);
+                    self.save_checkpoint();
+                    return None;
+                }
+                // if it can't be finished within this depth, come back to
+                // it later
+                if state.cost + state.heuristic > self.depth {
+                    self.deferred.push(state);
+                    continue;
+                }
+                self.best_coverage = self.best_coverage.max(state.coverage.count_ones() as usize);
+                if state.heuristic == 0 {
+                    let words = state
+                        .chain
+                        .iter()
+                        .map(|&i| self.solver.words.dictionary[i].clone())
+                        .collect();
+                    let solution = Solution::new(words);
+                    log::debug!(
+                        "found {:?} after expanding {} chains",
+                        solution.words,
+                        self.nodes_expanded
+                    );
+                    if self.next_checkpoint.is_some() {
+                        self.found.push(state.chain);
+                    }
+                    return Some(solution);
+                }
+                // no point making chains that are too long to ever be searched
+                if state.chain.len() >= self.max_words {
+                    self.nodes_pruned += 1;
+                } else {
+                    self.expand(&state);
+                    self.nodes_expanded += 1;
+                    self.peak_queue_size = self
+                        .peak_queue_size
+                        .max(self.priority_queue.len() + self.deferred.len());
+                    if self.solver.algorithm == Algorithm::Sma
+                        && self.priority_queue.len() + self.deferred.len() > self.solver.queue_limit
+                    {
+                        self.forget_worst();
+                    }
+                    if self.nodes_expanded.is_multiple_of(PROGRESS_INTERVAL) {
+                        self.report_progress(self.progress());
+                        if self
+                            .next_checkpoint
+                            .is_some_and(|next| Instant::now() >= next)
+                        {
+                            self.save_checkpoint();
+                        }
+                    }
+                }
+            }
+
+            if self.depth >= self.max_words || self.deferred.is_empty() || self.is_beaten() {
+                return None;
+            }
+
+            // move on to the next depth with everything put to one side
+            self.depth += 1;
+            self.priority_queue.extend(self.deferred.drain(..));
+            log::info!(
+                "searching chains of {} words, {} to carry on from",
+                self.depth,
+                self.priority_queue.len()
+            );
+            self.report_progress(self.progress());
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+// a little logging to see what the solver is up to, written to stderr once
+// the level is turned up, e.g. by -v and -vv on the command line. it's off
+// by default so the library stays quiet
+
+// what happened: the dictionary was read, the search moved on, etc
+pub const INFO: usize = 1;
+// the details, e.g. each solution as it's found
+pub const DEBUG: usize = 2;
+
+static LEVEL: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_level(level: usize) {
+    LEVEL.store(level, Ordering::Relaxed);
+}
+
+pub fn enabled(level: usize) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level
+}
+
+pub fn write(level: usize, message: fmt::Arguments) {
+    if enabled(level) {
+        let name = if level >= DEBUG { "debug" } else { "info" };
+        eprintln!("[{}] {}", name, message);
+    }
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => {
+        $crate::log::write($crate::log::INFO, format_args!($($arg)*))
+    };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        $crate::log::write($crate::log::DEBUG, format_args!($($arg)*))
+    };
+}
+
+pub(crate) use debug;
+pub(crate) use info;
+
+// a piece of work that's logged when it starts, and with how long it took
+// when it's dropped
+pub struct Span {
+    // none when the level is too low to log it
+    name: Option<String>,
+    level: usize,
+    start: Instant,
+}
+
+impl Span {
+    pub fn new(level: usize, name: fmt::Arguments) -> Span {
+        let name = enabled(level).then(|| name.to_string());
+        if let Some(name) = &name {
+            write(level, format_args!("{}...", name));
+        }
+        Span {
+            name,
+            level,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(name) = &self.name {
+            write(
+                self.level,
+                format_args!("{} took {:.2?}", name, self.start.elapsed()),
+            );
+        }
+    }
+}
@@ -1,344 +1,2392 @@
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet};
-use std::env::args_os;
-use std::fs::File;
-use std::io::{self, BufRead};
-// use std::sync::WaitTimeoutResult;
-
-#[derive(PartialEq, Eq)]
-struct State {
-    chain: Vec<String>,
-    last_word: String,
-    last_char: char,
-    cost: usize,
-    heuristic: usize,
+use std::collections::HashSet;
+use std::env::{self, args_os};
+use std::fs::{self, File};
+use std::io::{self, BufReader, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use dict::DictAction;
+use ignored::IgnoreEdit;
+use lbsolver::json;
+use lbsolver::{
+    Algorithm, BeeWord, Definitions, Dictionary, DictionaryCache, DictionaryFilter, Frequencies,
+    Generator, Heuristic, LbError, Puzzle, Random, RuleCheck, SearchStats, Solution, Solver,
+    SpellingBee, WordFilter,
+};
+use output::Output;
+
+mod batch;
+mod bench;
+mod clipboard;
+mod dict;
+mod draw;
+mod ignored;
+mod output;
+mod repl;
+mod schema;
+mod serve;
+mod today;
+mod tui;
+
+const USAGE: &str = "Usage: lbsolver [options] [solve] <group1> <group2> <group3> <group4> <ignore_word (opt)> <ignore_word (opt)> ...
+       lbsolver [options] repl
+       lbsolver [options] tui <group1> <group2> <group3> <group4> <ignore_word (opt)> ...
+       lbsolver [options] serve
+       lbsolver [options] batch <file>
+       lbsolver [options] gen [count]
+       lbsolver [options] difficulty <group1> <group2> <group3> <group4>
+       lbsolver [options] bench [board name] ...
+       lbsolver [options] check <group1> <group2> <group3> <group4> <word> <word> ...
+       lbsolver [options] hint <group1> <group2> <group3> <group4> --played <word>,<word>
+       lbsolver [options] bee <center letter> <other letters> <ignore_word (opt)> ...
+       lbsolver ignore add|remove <word> ... | ignore list
+       lbsolver dict compile <word list> [<file>.lbdict]
+       lbsolver [options] dict stats [<group1> <group2> <group3> <group4>]
+Each group must be 3 letters long (or --letters-per-side long)
+The board can also be given as one argument, abc-def-ghi-jkl or abcdefghijkl,
+or piped in when there are no arguments, e.g. echo \"abc def ghi jkl\" | lbsolver
+Any words after the groups of letters will be filtered out in the searching
+With --today the board (and the words the game accepts) come from the NYT site
+The repl loads the dictionary once and then solves boards typed in one at a time
+The tui shows the board and lets you browse, filter and ignore solutions
+batch solves every board in a file (- for stdin), one per line, each
+followed by any words to ignore for it
+gen makes random boards that can be solved in two words
+difficulty rates how hard a board is from 1 (easy) to 10 (hard)
+bench times reading the word list, then filtering and searching a set of
+built in boards (easy, medium, hard, four-words and unsolvable)
+check says whether a chain of words solves the board, and if not why not
+hint shows just the next word to play, after the words played so far
+bee finds the words for the NYT's Spelling Bee instead, from the same word
+lists, with the pangrams (words using every letter) marked
+ignore keeps a list of words to leave out of every solve, in
+$XDG_DATA_HOME/lbsolver/ignored.txt (or ~/.local/share/lbsolver/ignored.txt).
+whitelist.txt and blacklist.txt there are used the same as --whitelist and
+--blacklist on every run
+dict compile turns a word list into a .lbdict file, with the words' letters
+and where each letter's words are worked out ahead of time, so it loads
+quicker. a .lbdict can be used anywhere a word list can
+dict stats counts the words in the word lists, with the same options as a
+solve, by length and by their first and last letters, and with a board how
+many of them fit it
+Exits with 0 when solutions are found, 1 when the board has none (or check
+finds the chain doesn't solve it) and 2 for bad options, boards or word lists
+serve answers POST /solve with {\"sides\": [...], \"ignore\": [...], \"max_words\": n},
+or {\"board\": \"abc-def-ghi-jkl\", ...}, and POST /solve/stream with the same
+sends each solution as a line of JSON as soon as it's found
+
+Options:
+  -h, --help           show this help
+  --schema             print the JSON Schema for --format json's output
+  --dictionary <path>  word list to use, one word per line or a .lbdict
+                       from dict compile, can be given more than once to
+                       use the words from all of them
+                       (default: the yawl list in the current directory,
+                       next to the executable, or in the source directory)
+  --today              solve today's NYT board, all arguments are ignore words,
+                       uses the NYT word list unless --dictionary is given
+                       (needs curl)
+  --from-clipboard     solve the board copied to the clipboard, e.g. the
+                       letters copied from the NYT page (needs pbpaste,
+                       wl-paste, xclip or xsel)
+  --format <format>    how to print the solutions: text (default), json, or
+                       csv or tsv for a row per solution
+  --output <path>      write the solutions to this file instead, which is
+                       only replaced once they've all been written
+  --sides <n>          how many groups of letters the board has (default: 4)
+  --letters-per-side <n>
+                       how many letters are in each group (default: 3)
+  --threads <n>        how many threads to search with (default: 1)
+  --jobs <n>           how many boards batch solves at once, the output is
+                       still in the same order as the file (default: 1)
+  --algo <name>        how to search: astar (default), ida, which only
+                       keeps the chain it's looking at in memory, dfs,
+                       which goes through every chain up to --max-words,
+                       sma, A* that forgets the least promising chains
+                       when it has too many, beam, which only keeps the
+                       best chains at each length, both of which may miss
+                       the best ones, or join, which only finds chains of
+                       up to three words, without searching
+  --queue-limit <n>    the most chains sma keeps (default: 1000000)
+  --beam-width <n>     the most chains beam keeps (default: 1000)
+  --heuristic <name>   how to count the words a chain still needs: letters
+                       (default), from the most letters a word can add, or
+                       cover, which also counts the missing letters no word
+                       has two of, slower per chain but faster on hard boards
+  --weight <w>         weight the A* heuristic by this, above 1 finds a
+                       solution faster on hard boards but not always the
+                       shortest (default: 1)
+  --pairs              list every two word solution instead of searching
+  --played <words>     the words played so far, separated by commas, for hint
+  --seed <n>           the seed for gen's random boards (default: the time)
+  --limit <n>          show up to this many solutions (default: 4, or 1 when
+                       the best solutions have more than 3 words)
+  --all                show every solution with the fewest words, not just
+                       the first few
+  --top <n>            only show the best n solutions once they're sorted,
+                       out of 10n found with the fewest words (or --limit
+                       of them, or every one with --all), so --sort and
+                       --common-first pick them
+  --ignore-order       count solutions with the same words in a different
+                       order as the same solution, and only show the first
+  --sort <order>       how to order the solutions: letters (default, fewest
+                       letters first, then the fewest letters played twice),
+                       words (fewest words, then fewest letters, then
+                       alphabetically), alpha (alphabetically),
+                       rarity (by their least common word, most common
+                       first, needs --frequencies) or search (the order they
+                       were found in)
+  --frequencies <path> word frequency list, one word per line, most common
+                       first, optionally followed by its rank
+  --common-first       order the solutions by their least common word,
+                       needs --frequencies
+  --max-rank <n>       only use the n most common words in the --frequencies
+                       list, e.g. 30000 for words most people know
+  --strict-nyt         only use words the NYT game accepts, from --nyt-words
+                       or with --today the day's own list, so every answer
+                       can be played
+  --nyt-words <path>   the list of words the NYT game accepts, one per line
+  --no-plurals         leave out words that are another word in the list
+                       with an s or es on the end
+  --no-proper-nouns    leave out words that start with a capital letter
+  --no-abbreviations   leave out words with a full stop in them or written
+                       all in capitals
+  --explain            show which new letters each word in the solutions
+                       covers, and the sides its letters go between
+  --draw               draw the board under each solution, with the steps
+                       each letter is played at
+  --svg <path>         save a picture of the board with the lines the first
+                       solution draws, as an SVG file
+  --definitions <path> show what each word in the solutions means, from a
+                       file of words each followed by its meaning, or from
+                       WordNet's data files
+  --ignore-file <path> ignore the words in this file as well as the ones
+                       after the board, one per line, can be given more
+                       than once
+  --whitelist <path>   add the words in this file to the word lists, e.g.
+                       words the NYT game takes that they're missing, can be
+                       given more than once
+  --blacklist <path>   take the words in this file out of the word lists,
+                       can be given more than once
+  --require-word <word>
+                       only show solutions that use this word, can be
+                       given more than once
+  --start-with <word>  only show solutions that start with this word
+  --max-words <n>      don't look for solutions longer than this (default: 6)
+  --min-word-length <n>
+                       leave words shorter than this out of the solutions
+                       (default: 3)
+  --port <n>           the port serve listens on (default: 8080)
+  --host <address>     the address serve listens on, e.g. 0.0.0.0 to take
+                       requests from other machines (default: 127.0.0.1)
+  -v, --verbose        log what the solver is doing to stderr, -vv for more
+  -q, --quiet          only print the solutions, or nothing when there
+                       aren't any, without the board or which chain
+                       lengths came up empty
+  --timeout <seconds>  stop searching after this long and show the best
+                       solutions found by then
+  --max-nodes <n>      stop searching after expanding this many chains and
+                       show the best solutions found by then
+  --progress           show how far the search has got while it runs
+  --stats              say how much searching it took: the chains expanded
+                       and pruned, the most waiting at once, the words that
+                       fit the board and how long each step took, on stderr
+                       or as \"stats\" with --format json
+  --checkpoint <path>  save the search to this file every 30 seconds, and
+                       when --timeout stops it, so a long search (e.g. with
+                       --all) can be carried on with --resume
+  --resume <path>      carry on the search saved in this file, for the same
+                       board, word lists and options, saving it there again
+  --color <when>       color each letter of the solutions by its side, with
+                       the letters a word covers first in bold: auto
+                       (default, when printing to a terminal), always or never
+  --no-cache           don't use or write the copies of the word list kept in
+                       $XDG_CACHE_HOME/lbsolver (or ~/.cache/lbsolver)
+
+Environment (the command line options take precedence):
+  LBSOLVER_DICTIONARY  word lists to use, separated like PATH
+  LBSOLVER_FORMAT, LBSOLVER_OUTPUT, LBSOLVER_SORT, LBSOLVER_THREADS,
+  LBSOLVER_JOBS, LBSOLVER_ALGO, LBSOLVER_WEIGHT, LBSOLVER_QUEUE_LIMIT,
+  LBSOLVER_BEAM_WIDTH, LBSOLVER_HEURISTIC, LBSOLVER_MAX_WORDS,
+  LBSOLVER_MIN_WORD_LENGTH, LBSOLVER_LIMIT, LBSOLVER_TOP,
+  LBSOLVER_TIMEOUT, LBSOLVER_MAX_NODES, LBSOLVER_FREQUENCIES,
+  LBSOLVER_MAX_RANK, LBSOLVER_NYT_WORDS, LBSOLVER_DEFINITIONS,
+  LBSOLVER_IGNORE_FILE, LBSOLVER_WHITELIST, LBSOLVER_BLACKLIST,
+  LBSOLVER_PORT, LBSOLVER_HOST, LBSOLVER_COLOR
+                       the same as the options with those names
+  LBSOLVER_VERBOSE     how much to log, 1 for -v and 2 for -vv
+  LBSOLVER_PROGRESS, LBSOLVER_STATS, LBSOLVER_STRICT_NYT, LBSOLVER_NO_CACHE,
+  LBSOLVER_QUIET       set to 1 for --progress, --stats, --strict-nyt,
+                       --no-cache and --quiet";
+
+// what the exit code says about the run, so scripts can branch on it
+// without reading the output. 0 is solutions found
+const EXIT_NO_SOLUTION: i32 = 1;
+const EXIT_ERROR: i32 = 2;
+
+// how many solutions --top picks its best from for each one it shows,
+// when --limit doesn't say
+const TOP_CANDIDATES: usize = 10;
+
+// options that can be set in the environment instead, for containers and
+// scripts that would rather not build up a command line. they're read
+// before the command line so anything given there takes precedence
+const ENV_OPTIONS: &[(&str, &str)] = &[
+    ("LBSOLVER_FORMAT", "--format"),
+    ("LBSOLVER_OUTPUT", "--output"),
+    ("LBSOLVER_SORT", "--sort"),
+    ("LBSOLVER_THREADS", "--threads"),
+    ("LBSOLVER_JOBS", "--jobs"),
+    ("LBSOLVER_ALGO", "--algo"),
+    ("LBSOLVER_WEIGHT", "--weight"),
+    ("LBSOLVER_QUEUE_LIMIT", "--queue-limit"),
+    ("LBSOLVER_BEAM_WIDTH", "--beam-width"),
+    ("LBSOLVER_HEURISTIC", "--heuristic"),
+    ("LBSOLVER_MAX_WORDS", "--max-words"),
+    ("LBSOLVER_MIN_WORD_LENGTH", "--min-word-length"),
+    ("LBSOLVER_LIMIT", "--limit"),
+    ("LBSOLVER_TOP", "--top"),
+    ("LBSOLVER_TIMEOUT", "--timeout"),
+    ("LBSOLVER_MAX_NODES", "--max-nodes"),
+    ("LBSOLVER_FREQUENCIES", "--frequencies"),
+    ("LBSOLVER_MAX_RANK", "--max-rank"),
+    ("LBSOLVER_NYT_WORDS", "--nyt-words"),
+    ("LBSOLVER_DEFINITIONS", "--definitions"),
+    ("LBSOLVER_PORT", "--port"),
+    ("LBSOLVER_HOST", "--host"),
+    ("LBSOLVER_COLOR", "--color"),
+];
+const ENV_FLAGS: &[(&str, &str)] = &[
+    ("LBSOLVER_PROGRESS", "--progress"),
+    ("LBSOLVER_STATS", "--stats"),
+    ("LBSOLVER_STRICT_NYT", "--strict-nyt"),
+    ("LBSOLVER_NO_CACHE", "--no-cache"),
+    ("LBSOLVER_QUIET", "--quiet"),
+];
+
+// how the solutions are printed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    // a row per solution, for spreadsheets
+    Csv,
+    Tsv,
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (other.cost + other.heuristic).cmp(&(self.cost + self.heuristic))
+impl Format {
+    fn parse(name: &str) -> Result<Format, String> {
+        match name {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(format!(
+                "unknown format {}, expected text, json, csv or tsv",
+                name
+            )),
+        }
     }
-}
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    // what goes between the fields of a row, none if it isn't a table
+    fn separator(self) -> Option<char> {
+        match self {
+            Format::Csv => Some(','),
+            Format::Tsv => Some('\t'),
+            Format::Text | Format::Json => None,
+        }
     }
 }
 
-#[derive(Debug)]
-struct LetterBoxedSolver {
-    // letter_groups: Vec<Vec<char>>,
-    available_chars: HashSet<char>,
-    dictionary: Vec<String>,
-    start_letter_dictionary: HashMap<char, Vec<String>>,
-    // end_letter_dictionary: HashMap<char, Vec<String>>,
+// whether the solutions are printed in color
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Auto,
+    Always,
+    Never,
 }
 
-impl LetterBoxedSolver {
-    fn new(string_groups: &Vec<String>, source: File) -> LetterBoxedSolver {
-        let mut dictionary: Vec<String> = Vec::new();
-        let mut letter_groups: Vec<Vec<char>> = Vec::new();
-        let mut available_chars: HashSet<char> = HashSet::new();
-        let mut start_letter_dictionary: HashMap<char, Vec<String>> = HashMap::new();
-        let mut end_letter_dictionary: HashMap<char, Vec<String>> = HashMap::new();
+impl Color {
+    fn parse(name: &str) -> Result<Color, String> {
+        match name {
+            "auto" => Ok(Color::Auto),
+            "always" => Ok(Color::Always),
+            "never" => Ok(Color::Never),
+            _ => Err(format!(
+                "unknown color setting {}, expected auto, always or never",
+                name
+            )),
+        }
+    }
 
-        for group in string_groups {
-            let mut chars: Vec<char> = group.chars().collect();
-            if chars.len() != 3 {
-                panic!("Each group of letters must be 3 letters long");
+    // auto only colors a terminal, and respects NO_COLOR
+    fn enabled(self, to_stdout: bool) -> bool {
+        match self {
+            Color::Auto => {
+                to_stdout
+                    && io::stdout().is_terminal()
+                    && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
             }
-            chars.sort();
-            letter_groups.push(chars.clone());
-            available_chars.extend(chars.clone());
+            Color::Always => true,
+            Color::Never => false,
         }
+    }
+}
 
-        // check if there are duplicate letters in the available chars
-        let no_duplicate_letters = available_chars.len() == 12;
+// the order the solutions are printed in
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Sort {
+    Search,
+    Letters,
+    Words,
+    Alpha,
+    Rarity,
+}
 
-        let lines = io::BufReader::new(source).lines();
-        for line in lines {
-            if let Ok(word) = line {
-                // we can't use words longer than 12 letters or shorter than 3 letters
-                if word.len() > 12 || word.len() < 3 {
-                    continue;
-                }
-                // only push if the word has letters that are all in the available chars
-                let word_chars: HashSet<char> = word.chars().collect();
+impl Sort {
+    fn parse(name: &str) -> Result<Sort, String> {
+        match name {
+            "search" => Ok(Sort::Search),
+            "letters" => Ok(Sort::Letters),
+            "words" => Ok(Sort::Words),
+            "alpha" => Ok(Sort::Alpha),
+            "rarity" => Ok(Sort::Rarity),
+            _ => Err(format!(
+                "unknown sort order {}, expected letters, words, alpha, rarity or search",
+                name
+            )),
+        }
+    }
+}
 
-                // if no duplicates in the letter groups then
-                // check if there are duplicate letters in the word and reject if there are
-                if no_duplicate_letters && (word_chars.len() != word.len()) {
-                    continue;
-                }
+// which mode to run in, given as the first argument
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Solve,
+    Repl,
+    Tui,
+    Serve,
+    Batch,
+    Hint,
+    Check,
+    Gen,
+    Difficulty,
+    Bench,
+    Bee,
+    Ignore,
+    Dict,
+}
 
-                let diff: HashSet<_> = word_chars.difference(&available_chars).collect();
-                // if there is any difference (length > 0), then don't add this word to the dictionary
-                if diff.len() > 0 {
-                    continue;
-                }
+// what was asked for on the command line
+struct Args {
+    command: Command,
+    // the file of boards for batch, - for stdin
+    batch_file: Option<PathBuf>,
+    // what to do with the saved ignore words, for ignore
+    ignore_edit: Option<IgnoreEdit>,
+    // what to do with a word list, for dict
+    dict_action: Option<DictAction>,
+    // the names of the boards bench runs, all of them when empty
+    bench_boards: Vec<String>,
+    groups: Vec<String>,
+    sides: usize,
+    group_size: usize,
+    threads: usize,
+    // how many boards batch solves at once
+    jobs: usize,
+    algorithm: Algorithm,
+    heuristic: Heuristic,
+    weight: f64,
+    queue_limit: Option<usize>,
+    beam_width: Option<usize>,
+    pairs: bool,
+    sort: Sort,
+    ignore_order: bool,
+    limit: Option<usize>,
+    all: bool,
+    // how many of the solutions found to show, after sorting them
+    top: Option<usize>,
+    // the words already played, for hint
+    played: Vec<String>,
+    // the proposed solution, for check
+    chain: Vec<String>,
+    // how many boards gen makes, and where its random numbers start
+    count: usize,
+    seed: Option<u64>,
+    frequencies: Option<PathBuf>,
+    common_first: bool,
+    // only use words this common in the frequency list
+    max_rank: Option<usize>,
+    // only use words the NYT accepts, from this list or today's
+    strict_nyt: bool,
+    nyt_words: Option<PathBuf>,
+    // the kinds of word left out of the word lists as they're read
+    dictionary_filter: DictionaryFilter,
+    definitions: Option<PathBuf>,
+    // show what each word of the solutions adds to the chain
+    explain: bool,
+    // draw the board with the order each solution plays its letters in
+    draw: bool,
+    // where to save a picture of the board and the first solution
+    svg: Option<PathBuf>,
+    required_words: Vec<String>,
+    start_word: Option<String>,
+    max_words: usize,
+    min_word_length: usize,
+    today: bool,
+    ignore_words: Vec<String>,
+    // words added to and taken out of the word lists
+    whitelist: Vec<String>,
+    blacklist: Vec<String>,
+    // the word lists to use, none for the default
+    dictionaries: Vec<PathBuf>,
+    format: Format,
+    // the file to write the solutions to, none for stdout
+    output: Option<PathBuf>,
+    // color the letters of the solutions
+    color: bool,
+    port: u16,
+    host: String,
+    // keep preprocessed word lists in the user's cache directory
+    cache: bool,
+    progress: bool,
+    stats: bool,
+    // how much to log, 0 for nothing
+    verbosity: usize,
+    // leave out everything but the solutions, for piping them elsewhere
+    quiet: bool,
+    timeout: Option<Duration>,
+    max_nodes: Option<usize>,
+    // where the search saves its state, and the saved search to carry on
+    checkpoint: Option<PathBuf>,
+    resume: Option<PathBuf>,
+}
 
-                // this works out if the words in the dictionary are valid
-                // for these letter groups
-                let is_letter_box_word =
-                    LetterBoxedSolver::is_letter_pattern_in_letter_box(&letter_groups, &word);
-                if !is_letter_box_word {
-                    continue;
-                }
+// the options set in the environment, as if they'd been given as arguments
+fn env_args() -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    for &(name, option) in ENV_OPTIONS {
+        if let Some(value) = env_var(name)? {
+            args.push(format!("{}={}", option, value));
+        }
+    }
+    for &(name, flag) in ENV_FLAGS {
+        if env_var(name)?.is_some_and(|value| value != "0") {
+            args.push(flag.to_string());
+        }
+    }
+    Ok(args)
+}
+
+// an environment variable, none if it isn't set or is empty
+fn env_var(name: &str) -> Result<Option<String>, String> {
+    match env::var(name) {
+        Ok(value) if value.is_empty() => Ok(None),
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(value)) => {
+            Err(format!("{} {:?} is not valid unicode", name, value))
+        }
+    }
+}
 
-                dictionary.push(word.clone());
-                let start_letter = word.chars().next().unwrap();
-                let end_letter = word.chars().last().unwrap();
+fn parse_args() -> Result<Args, String> {
+    // turn args into Vec<String>, after the ones from the environment
+    let mut args = env_args()?;
+    for arg in args_os().skip(1) {
+        args.push(
+            arg.into_string()
+                .map_err(|arg| format!("argument {:?} is not valid unicode", arg))?,
+        );
+    }
 
-                // add to start letter dictionary
-                if start_letter_dictionary.contains_key(&start_letter) {
-                    let words = start_letter_dictionary.get_mut(&start_letter).unwrap();
-                    words.push(word.clone());
-                } else {
-                    start_letter_dictionary.insert(start_letter, vec![word.clone()]);
-                }
+    let mut positional: Vec<String> = Vec::new();
+    let mut dictionaries = Vec::new();
+    let mut format = Format::Text;
+    let mut output = None;
+    let mut color = Color::Auto;
+    let mut sides: usize = 4;
+    let mut group_size: usize = 3;
+    let mut threads: usize = 1;
+    let mut jobs: usize = 1;
+    let mut algorithm = Algorithm::AStar;
+    let mut heuristic = Heuristic::Letters;
+    let mut weight = 1.0;
+    let mut queue_limit = None;
+    let mut beam_width = None;
+    let mut pairs = false;
+    let mut sort = Sort::Letters;
+    let mut ignore_order = false;
+    let mut limit = None;
+    let mut top = None;
+    let mut all = false;
+    let mut played = Vec::new();
+    let mut seed = None;
+    let mut frequencies = None;
+    let mut common_first = false;
+    let mut max_rank = None;
+    let mut strict_nyt = false;
+    let mut nyt_words = None;
+    let mut dictionary_filter = DictionaryFilter::default();
+    let mut definitions = None;
+    let mut explain = false;
+    let mut draw = false;
+    let mut svg = None;
+    let mut required_words = Vec::new();
+    let mut ignore_files = Vec::new();
+    let mut whitelist_files = Vec::new();
+    let mut blacklist_files = Vec::new();
+    let mut start_word = None;
+    let mut max_words: usize = 6;
+    let mut min_word_length: usize = 3;
+    let mut today = false;
+    let mut from_clipboard = false;
+    let mut port: u16 = 8080;
+    let mut host = "127.0.0.1".to_string();
+    let mut cache = true;
+    let mut progress = false;
+    let mut stats = false;
+    let mut verbosity: usize = 0;
+    let mut quiet = false;
+    let mut timeout = None;
+    let mut max_nodes = None;
+    let mut checkpoint = None;
+    let mut resume = None;
 
-                // add to end letter dictionary
-                if end_letter_dictionary.contains_key(&end_letter) {
-                    let words = end_letter_dictionary.get_mut(&end_letter).unwrap();
-                    words.push(word.clone());
-                } else {
-                    end_letter_dictionary.insert(end_letter, vec![word.clone()]);
-                }
-            }
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--dictionary" {
+            let path = args.next().ok_or("--dictionary needs a path")?;
+            dictionaries.push(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--dictionary=") {
+            dictionaries.push(PathBuf::from(path));
+        } else if arg == "--color" {
+            color = Color::parse(&args.next().ok_or("--color needs a value")?)?;
+        } else if let Some(name) = arg.strip_prefix("--color=") {
+            color = Color::parse(name)?;
+        } else if arg == "--format" {
+            format = Format::parse(&args.next().ok_or("--format needs a value")?)?;
+        } else if let Some(name) = arg.strip_prefix("--format=") {
+            format = Format::parse(name)?;
+        } else if arg == "--output" {
+            let path = args.next().ok_or("--output needs a path")?;
+            output = Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--output=") {
+            output = Some(PathBuf::from(path));
+        } else if arg == "--sides" {
+            sides = parse_sides(&args.next().ok_or("--sides needs a number")?)?;
+        } else if let Some(count) = arg.strip_prefix("--sides=") {
+            sides = parse_sides(count)?;
+        } else if arg == "--letters-per-side" {
+            group_size =
+                parse_group_size(&args.next().ok_or("--letters-per-side needs a number")?)?;
+        } else if let Some(size) = arg.strip_prefix("--letters-per-side=") {
+            group_size = parse_group_size(size)?;
+        } else if arg == "--algo" {
+            algorithm = parse_algorithm(&args.next().ok_or("--algo needs a value")?)?;
+        } else if let Some(name) = arg.strip_prefix("--algo=") {
+            algorithm = parse_algorithm(name)?;
+        } else if arg == "--heuristic" {
+            heuristic = parse_heuristic(&args.next().ok_or("--heuristic needs a value")?)?;
+        } else if let Some(name) = arg.strip_prefix("--heuristic=") {
+            heuristic = parse_heuristic(name)?;
+        } else if arg == "--weight" {
+            weight = parse_weight(&args.next().ok_or("--weight needs a number")?)?;
+        } else if let Some(number) = arg.strip_prefix("--weight=") {
+            weight = parse_weight(number)?;
+        } else if arg == "--queue-limit" {
+            queue_limit = Some(parse_queue_limit(
+                &args.next().ok_or("--queue-limit needs a number")?,
+            )?);
+        } else if let Some(count) = arg.strip_prefix("--queue-limit=") {
+            queue_limit = Some(parse_queue_limit(count)?);
+        } else if arg == "--beam-width" {
+            beam_width = Some(parse_beam_width(
+                &args.next().ok_or("--beam-width needs a number")?,
+            )?);
+        } else if let Some(count) = arg.strip_prefix("--beam-width=") {
+            beam_width = Some(parse_beam_width(count)?);
+        } else if arg == "--threads" {
+            threads = parse_threads(&args.next().ok_or("--threads needs a number")?)?;
+        } else if let Some(count) = arg.strip_prefix("--threads=") {
+            threads = parse_threads(count)?;
+        } else if arg == "--jobs" {
+            jobs = parse_jobs(&args.next().ok_or("--jobs needs a number")?)?;
+        } else if let Some(count) = arg.strip_prefix("--jobs=") {
+            jobs = parse_jobs(count)?;
+        } else if arg == "--pairs" {
+            pairs = true;
+        } else if arg == "--limit" {
+            limit = Some(parse_limit(&args.next().ok_or("--limit needs a number")?)?);
+        } else if let Some(count) = arg.strip_prefix("--limit=") {
+            limit = Some(parse_limit(count)?);
+        } else if arg == "--top" {
+            top = Some(parse_top(&args.next().ok_or("--top needs a number")?)?);
+        } else if let Some(count) = arg.strip_prefix("--top=") {
+            top = Some(parse_top(count)?);
+        } else if arg == "--played" {
+            played.extend(parse_played(&args.next().ok_or("--played needs words")?));
+        } else if let Some(words) = arg.strip_prefix("--played=") {
+            played.extend(parse_played(words));
+        } else if arg == "--seed" {
+            seed = Some(parse_seed(&args.next().ok_or("--seed needs a number")?)?);
+        } else if let Some(number) = arg.strip_prefix("--seed=") {
+            seed = Some(parse_seed(number)?);
+        } else if arg == "--all" {
+            all = true;
+        } else if arg == "--ignore-order" {
+            ignore_order = true;
+        } else if arg == "--sort" {
+            sort = Sort::parse(&args.next().ok_or("--sort needs a value")?)?;
+        } else if let Some(name) = arg.strip_prefix("--sort=") {
+            sort = Sort::parse(name)?;
+        } else if arg == "--frequencies" {
+            let path = args.next().ok_or("--frequencies needs a path")?;
+            frequencies = Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--frequencies=") {
+            frequencies = Some(PathBuf::from(path));
+        } else if arg == "--common-first" {
+            common_first = true;
+        } else if arg == "--max-rank" {
+            max_rank = Some(parse_max_rank(
+                &args.next().ok_or("--max-rank needs a number")?,
+            )?);
+        } else if let Some(rank) = arg.strip_prefix("--max-rank=") {
+            max_rank = Some(parse_max_rank(rank)?);
+        } else if arg == "--no-plurals" {
+            dictionary_filter.plurals = true;
+        } else if arg == "--no-proper-nouns" {
+            dictionary_filter.proper_nouns = true;
+        } else if arg == "--no-abbreviations" {
+            dictionary_filter.abbreviations = true;
+        } else if arg == "--strict-nyt" {
+            strict_nyt = true;
+        } else if arg == "--nyt-words" {
+            let path = args.next().ok_or("--nyt-words needs a path")?;
+            nyt_words = Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--nyt-words=") {
+            nyt_words = Some(PathBuf::from(path));
+        } else if arg == "--explain" {
+            explain = true;
+        } else if arg == "--draw" {
+            draw = true;
+        } else if arg == "--svg" {
+            let path = args.next().ok_or("--svg needs a path")?;
+            svg = Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--svg=") {
+            svg = Some(PathBuf::from(path));
+        } else if arg == "--definitions" {
+            let path = args.next().ok_or("--definitions needs a path")?;
+            definitions = Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--definitions=") {
+            definitions = Some(PathBuf::from(path));
+        } else if arg == "--ignore-file" {
+            let path = args.next().ok_or("--ignore-file needs a path")?;
+            ignore_files.push(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--ignore-file=") {
+            ignore_files.push(PathBuf::from(path));
+        } else if arg == "--whitelist" {
+            let path = args.next().ok_or("--whitelist needs a path")?;
+            whitelist_files.push(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--whitelist=") {
+            whitelist_files.push(PathBuf::from(path));
+        } else if arg == "--blacklist" {
+            let path = args.next().ok_or("--blacklist needs a path")?;
+            blacklist_files.push(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--blacklist=") {
+            blacklist_files.push(PathBuf::from(path));
+        } else if arg == "--require-word" {
+            required_words.push(args.next().ok_or("--require-word needs a word")?);
+        } else if let Some(word) = arg.strip_prefix("--require-word=") {
+            required_words.push(word.to_string());
+        } else if arg == "--start-with" {
+            start_word = Some(args.next().ok_or("--start-with needs a word")?);
+        } else if let Some(word) = arg.strip_prefix("--start-with=") {
+            start_word = Some(word.to_string());
+        } else if arg == "--max-words" {
+            max_words = parse_max_words(&args.next().ok_or("--max-words needs a number")?)?;
+        } else if let Some(count) = arg.strip_prefix("--max-words=") {
+            max_words = parse_max_words(count)?;
+        } else if arg == "--min-word-length" {
+            min_word_length =
+                parse_min_word_length(&args.next().ok_or("--min-word-length needs a number")?)?;
+        } else if let Some(length) = arg.strip_prefix("--min-word-length=") {
+            min_word_length = parse_min_word_length(length)?;
+        } else if arg == "--today" {
+            today = true;
+        } else if arg == "--from-clipboard" {
+            from_clipboard = true;
+        } else if arg == "--port" {
+            port = parse_port(&args.next().ok_or("--port needs a number")?)?;
+        } else if let Some(number) = arg.strip_prefix("--port=") {
+            port = parse_port(number)?;
+        } else if arg == "--host" {
+            host = args.next().ok_or("--host needs an address")?;
+        } else if let Some(address) = arg.strip_prefix("--host=") {
+            host = address.to_string();
+        } else if arg == "-v" || arg == "--verbose" {
+            verbosity += 1;
+        } else if arg == "-vv" {
+            verbosity += 2;
+        } else if arg == "-q" || arg == "--quiet" {
+            quiet = true;
+        } else if arg == "--timeout" {
+            timeout = Some(parse_timeout(
+                &args.next().ok_or("--timeout needs a number of seconds")?,
+            )?);
+        } else if let Some(seconds) = arg.strip_prefix("--timeout=") {
+            timeout = Some(parse_timeout(seconds)?);
+        } else if arg == "--max-nodes" {
+            max_nodes = Some(parse_max_nodes(
+                &args.next().ok_or("--max-nodes needs a number")?,
+            )?);
+        } else if let Some(count) = arg.strip_prefix("--max-nodes=") {
+            max_nodes = Some(parse_max_nodes(count)?);
+        } else if arg == "--checkpoint" {
+            let path = args.next().ok_or("--checkpoint needs a path")?;
+            checkpoint = Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--checkpoint=") {
+            checkpoint = Some(PathBuf::from(path));
+        } else if arg == "--resume" {
+            let path = args.next().ok_or("--resume needs a path")?;
+            resume = Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--resume=") {
+            resume = Some(PathBuf::from(path));
+        } else if arg == "--progress" {
+            progress = true;
+        } else if arg == "--stats" {
+            stats = true;
+        } else if arg == "--no-cache" {
+            cache = false;
+        } else if arg.starts_with("--") {
+            return Err(format!("unknown option {}", arg));
+        } else {
+            positional.push(arg);
+        }
+    }
+
+    // these add up rather than replace each other, so they only come from
+    // the environment when the command line doesn't give any
+    if dictionaries.is_empty() {
+        if let Some(paths) = env::var_os("LBSOLVER_DICTIONARY").filter(|paths| !paths.is_empty()) {
+            dictionaries = env::split_paths(&paths).collect();
+        }
+    }
+    if ignore_files.is_empty() {
+        if let Some(path) = env_var("LBSOLVER_IGNORE_FILE")? {
+            ignore_files.push(PathBuf::from(path));
+        }
+    }
+    if whitelist_files.is_empty() {
+        if let Some(path) = env_var("LBSOLVER_WHITELIST")? {
+            whitelist_files.push(PathBuf::from(path));
+        }
+    }
+    if blacklist_files.is_empty() {
+        if let Some(path) = env_var("LBSOLVER_BLACKLIST")? {
+            blacklist_files.push(PathBuf::from(path));
+        }
+    }
+    if verbosity == 0 {
+        if let Some(level) = env_var("LBSOLVER_VERBOSE")? {
+            verbosity = level
+                .parse()
+                .map_err(|_| format!("LBSOLVER_VERBOSE {:?} is not a number", level))?;
         }
+    }
 
-        // reorder dictionary by word length, longest first
-        dictionary.sort_by(|a, b| b.len().cmp(&a.len()));
+    let queued = matches!(algorithm, Algorithm::AStar | Algorithm::Sma);
+    if weight != 1.0 && !queued {
+        return Err("--weight only works with --algo astar or sma".to_string());
+    }
+    if queue_limit.is_some() && algorithm != Algorithm::Sma {
+        return Err("--queue-limit only works with --algo sma".to_string());
+    }
+    if beam_width.is_some() && algorithm != Algorithm::Beam {
+        return Err("--beam-width only works with --algo beam".to_string());
+    }
+    if common_first && frequencies.is_none() {
+        return Err("--common-first needs a --frequencies list".to_string());
+    }
+    if sort == Sort::Rarity && frequencies.is_none() {
+        return Err("--sort rarity needs a --frequencies list".to_string());
+    }
+    if max_rank.is_some() && frequencies.is_none() {
+        return Err("--max-rank needs a --frequencies list".to_string());
+    }
+    if strict_nyt && nyt_words.is_none() && !today {
+        return Err("--strict-nyt needs a --nyt-words list, or --today".to_string());
+    }
+    if nyt_words.is_some() && !strict_nyt {
+        return Err("--nyt-words is only used with --strict-nyt".to_string());
+    }
 
-        LetterBoxedSolver {
-            // letter_groups,
-            available_chars,
-            dictionary,
-            start_letter_dictionary,
-            // end_letter_dictionary,
+    let command = match positional.first().map(String::as_str) {
+        Some("repl") => Command::Repl,
+        Some("tui") => Command::Tui,
+        Some("serve") => Command::Serve,
+        Some("batch") => Command::Batch,
+        Some("hint") => Command::Hint,
+        Some("check") => Command::Check,
+        Some("gen") => Command::Gen,
+        Some("difficulty") => Command::Difficulty,
+        Some("bench") => Command::Bench,
+        Some("bee") => Command::Bee,
+        Some("ignore") => Command::Ignore,
+        Some("dict") => Command::Dict,
+        _ => Command::Solve,
+    };
+    // solving is what happens without a command, but it can be named too
+    if command != Command::Solve || positional.first().is_some_and(|first| first == "solve") {
+        positional.remove(0);
+    }
+    if !played.is_empty() && command != Command::Hint {
+        return Err("--played is only used by hint".to_string());
+    }
+    if seed.is_some() && command != Command::Gen {
+        return Err("--seed is only used by gen".to_string());
+    }
+    if jobs > 1 && command != Command::Batch {
+        return Err("--jobs is only used by batch".to_string());
+    }
+    if pairs && !matches!(command, Command::Solve | Command::Batch) {
+        return Err("--pairs is only used when solving boards".to_string());
+    }
+    if stats && command != Command::Solve {
+        return Err("--stats is only used by solve".to_string());
+    }
+    if output.is_some() && !matches!(command, Command::Solve | Command::Batch) {
+        return Err("--output is only used when solving boards".to_string());
+    }
+    // only the A* search on one thread can be saved
+    if checkpoint.is_some() || resume.is_some() {
+        if command != Command::Solve || pairs {
+            return Err("--checkpoint and --resume are only used by solve".to_string());
+        }
+        if !queued || threads > 1 {
+            return Err(
+                "--checkpoint and --resume only work with --algo astar or sma on one thread"
+                    .to_string(),
+            );
         }
     }
+    // a resumed search keeps saving to the file it came from
+    let checkpoint = checkpoint.or_else(|| resume.clone());
+    // the repl gets its boards from what's typed into it, serve from its
+    // requests, and --today gets it from the NYT
+    let count = if command == Command::Gen && !positional.is_empty() {
+        let count = positional.remove(0);
+        count
+            .parse::<usize>()
+            .map_err(|_| format!("gen needs a number of boards to make, got {}", count))?
+    } else {
+        1
+    };
+    // bench's arguments are the names of the boards to run
+    let bench_boards = if command == Command::Bench {
+        positional.split_off(0)
+    } else {
+        Vec::new()
+    };
+    let batch_file = if command == Command::Batch {
+        if positional.is_empty() {
+            return Err("batch needs a file of boards".to_string());
+        }
+        Some(PathBuf::from(positional.remove(0)))
+    } else {
+        None
+    };
+    let needs_board = matches!(
+        command,
+        Command::Solve | Command::Tui | Command::Hint | Command::Check | Command::Difficulty
+    );
+    // the board from the clipboard goes before any words to ignore
+    if needs_board && from_clipboard {
+        if today {
+            return Err("--from-clipboard and --today both give the board".to_string());
+        }
+        positional.splice(0..0, clipboard::board_arguments(&clipboard::read()?));
+    }
+    // with nothing on the command line the board, and any words to ignore,
+    // can be piped in instead
+    if needs_board && !today && positional.is_empty() && !io::stdin().is_terminal() {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|err| format!("could not read the board from stdin ({})", err))?;
+        positional.extend(input.split_whitespace().map(str::to_string));
+    }
+    // the board can be given as one argument, the way it's usually shared
+    if needs_board
+        && !today
+        && positional.first().is_some_and(|first| {
+            first.contains(['-', '/', ',']) || first.chars().count() == sides * group_size
+        })
+    {
+        let groups = lbsolver::split_board(&positional[0], sides).map_err(|err| err.to_string())?;
+        positional.splice(0..1, groups);
+    }
+    if needs_board && !today && positional.len() < sides {
+        return Err(format!("expected {} groups of letters", sides));
+    }
+    // ignore's arguments are what to do with the saved words
+    let ignore_edit = if command == Command::Ignore {
+        Some(IgnoreEdit::parse(positional.split_off(0))?)
+    } else {
+        None
+    };
+    // and dict's are what to do with a word list
+    let dict_action = if command == Command::Dict {
+        Some(DictAction::parse(positional.split_off(0))?)
+    } else {
+        None
+    };
+    // a spelling bee's center letter and the others take the board's place
+    let board_size = if command == Command::Bee { 2 } else { sides };
+    if command == Command::Bee && positional.len() < board_size {
+        return Err("bee needs the center letter and the other letters".to_string());
+    }
 
-    // this is the solver part of the program
-    fn run_solver(&mut self, ignore_words: &Vec<String>) -> Result<Vec<Vec<String>>, String> {
-        // for c in self.available_chars.clone() {
-        //     let mut words = self.start_letter_dictionary.get_mut(&c).unwrap();
-        //     println!("{}: {:?}", c, words.len());
-        // }
+    // batch boards come from its file, so anything else is words to ignore
+    let mut ignore_words = if today || command == Command::Batch {
+        positional.split_off(0)
+    } else {
+        positional.split_off(board_size.min(positional.len()))
+    };
+    // words are matched in lowercase, however they were typed
+    for word in ignore_words.iter_mut().chain(&mut required_words) {
+        *word = word.to_lowercase();
+    }
+    let start_word = start_word.map(|word| word.to_lowercase());
+    // check takes the words after the board as the chain to check
+    let chain = if command == Command::Check {
+        std::mem::take(&mut ignore_words)
+    } else {
+        Vec::new()
+    };
+    for path in &ignore_files {
+        ignore_words.extend(read_ignore_file(path)?);
+    }
+    let mut whitelist = Vec::new();
+    for path in &whitelist_files {
+        whitelist.extend(read_ignore_file(path)?);
+    }
+    let mut blacklist = Vec::new();
+    for path in &blacklist_files {
+        blacklist.extend(read_ignore_file(path)?);
+    }
+    // compiling a word list only needs the list
+    if command != Command::Ignore && !matches!(dict_action, Some(DictAction::Compile { .. })) {
+        ignore_words.extend(ignored::load()?);
+        whitelist.extend(ignored::load_saved(ignored::WHITELIST)?);
+        blacklist.extend(ignored::load_saved(ignored::BLACKLIST)?);
+    }
+
+    let color = color.enabled(output.is_none());
+    Ok(Args {
+        command,
+        batch_file,
+        ignore_edit,
+        dict_action,
+        bench_boards,
+        groups: positional,
+        sides,
+        group_size,
+        threads,
+        jobs,
+        algorithm,
+        heuristic,
+        weight,
+        queue_limit,
+        beam_width,
+        pairs,
+        sort,
+        ignore_order,
+        limit,
+        top,
+        all,
+        played,
+        chain,
+        count,
+        seed,
+        frequencies,
+        common_first,
+        max_rank,
+        strict_nyt,
+        nyt_words,
+        dictionary_filter,
+        definitions,
+        explain,
+        draw,
+        svg,
+        required_words,
+        start_word,
+        max_words,
+        min_word_length,
+        today,
+        ignore_words,
+        whitelist,
+        blacklist,
+        dictionaries,
+        format,
+        output,
+        color,
+        port,
+        host,
+        cache,
+        progress,
+        stats,
+        verbosity,
+        quiet,
+        timeout,
+        max_nodes,
+        checkpoint,
+        resume,
+    })
+}
 
-        return Ok(self.a_star(ignore_words).unwrap());
+fn parse_sides(count: &str) -> Result<usize, String> {
+    match count.parse::<usize>() {
+        Ok(sides) if sides >= 2 => Ok(sides),
+        _ => Err(format!(
+            "--sides must be a number of 2 or more, got {}",
+            count
+        )),
     }
+}
 
-    // useful for A* search
-    fn heuristic(&self, chain: &Vec<String>, chars: &HashSet<char>) -> usize {
-        let chain_chars: HashSet<char> = chain.iter().flat_map(|word| word.chars()).collect();
-        let diff = chars.difference(&chain_chars).count();
+fn parse_group_size(size: &str) -> Result<usize, String> {
+    match size.parse::<usize>() {
+        Ok(group_size) if group_size >= 1 => Ok(group_size),
+        _ => Err(format!(
+            "--letters-per-side must be a number of 1 or more, got {}",
+            size
+        )),
+    }
+}
 
-        let mut a = chars.into_iter().collect::<Vec<_>>();
-        let mut b = chain_chars.into_iter().collect::<Vec<_>>();
-        a.sort();
-        b.sort();
+fn parse_algorithm(name: &str) -> Result<Algorithm, String> {
+    match name {
+        "astar" => Ok(Algorithm::AStar),
+        "ida" => Ok(Algorithm::Ida),
+        "dfs" => Ok(Algorithm::Dfs),
+        "sma" => Ok(Algorithm::Sma),
+        "beam" => Ok(Algorithm::Beam),
+        "join" => Ok(Algorithm::Join),
+        _ => Err(format!(
+            "unknown algorithm {}, expected astar, ida, dfs, sma, beam or join",
+            name
+        )),
+    }
+}
+
+// the words in an ignore, whitelist or blacklist file, lowercased,
+// skipping blank lines and comments starting with #
+fn read_ignore_file(path: &Path) -> Result<Vec<String>, String> {
+    let file =
+        File::open(path).map_err(|err| format!("could not read {} ({})", path.display(), err))?;
+    let entries = lbsolver::compiled::read_entries(file)
+        .map_err(|err| format!("could not read {} ({})", path.display(), err))?;
+    Ok(entries
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+        .collect())
+}
+
+fn parse_heuristic(name: &str) -> Result<Heuristic, String> {
+    match name {
+        "letters" => Ok(Heuristic::Letters),
+        "cover" => Ok(Heuristic::Cover),
+        _ => Err(format!(
+            "unknown heuristic {}, expected letters or cover",
+            name
+        )),
+    }
+}
+
+fn parse_weight(number: &str) -> Result<f64, String> {
+    match number.parse::<f64>() {
+        Ok(weight) if weight >= 1.0 && weight.is_finite() => Ok(weight),
+        _ => Err(format!(
+            "--weight must be a number of 1 or more, got {}",
+            number
+        )),
+    }
+}
+
+fn parse_threads(count: &str) -> Result<usize, String> {
+    match count.parse::<usize>() {
+        Ok(threads) if threads >= 1 => Ok(threads),
+        _ => Err(format!(
+            "--threads must be a number of 1 or more, got {}",
+            count
+        )),
+    }
+}
+
+fn parse_queue_limit(count: &str) -> Result<usize, String> {
+    match count.parse::<usize>() {
+        Ok(limit) if limit >= 1 => Ok(limit),
+        _ => Err(format!(
+            "--queue-limit must be a number of 1 or more, got {}",
+            count
+        )),
+    }
+}
+
+fn parse_beam_width(count: &str) -> Result<usize, String> {
+    match count.parse::<usize>() {
+        Ok(width) if width >= 1 => Ok(width),
+        _ => Err(format!(
+            "--beam-width must be a number of 1 or more, got {}",
+            count
+        )),
+    }
+}
+
+fn parse_jobs(count: &str) -> Result<usize, String> {
+    match count.parse::<usize>() {
+        Ok(jobs) if jobs >= 1 => Ok(jobs),
+        _ => Err(format!(
+            "--jobs must be a number of 1 or more, got {}",
+            count
+        )),
+    }
+}
+
+fn parse_max_words(count: &str) -> Result<usize, String> {
+    match count.parse::<usize>() {
+        Ok(max_words) if max_words >= 1 => Ok(max_words),
+        _ => Err(format!(
+            "--max-words must be a number of 1 or more, got {}",
+            count
+        )),
+    }
+}
 
-        // println!("Chars: {}", a.into_iter().collect::<String>());
-        // println!("------------------------------");
-        // println!("Chain: {:?}", chain);
-        // println!("ch-ch: {}", b.into_iter().collect::<String>());
-        // println!(" diff: {:?}", diff);
-        // println!("\n---\n");
+fn parse_timeout(seconds: &str) -> Result<Duration, String> {
+    match seconds
+        .parse::<f64>()
+        .ok()
+        .filter(|&seconds| seconds > 0.0)
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+    {
+        Some(timeout) => Ok(timeout),
+        None => Err(format!(
+            "--timeout must be a number of seconds, got {}",
+            seconds
+        )),
+    }
+}
 
-        diff
+fn parse_max_nodes(count: &str) -> Result<usize, String> {
+    match count.parse::<usize>() {
+        Ok(max_nodes) if max_nodes >= 1 => Ok(max_nodes),
+        _ => Err(format!(
+            "--max-nodes must be a number of 1 or more, got {}",
+            count
+        )),
     }
+}
+
+fn parse_port(number: &str) -> Result<u16, String> {
+    number
+        .parse::<u16>()
+        .map_err(|_| format!("--port must be a port number, got {}", number))
+}
+
+fn parse_played(words: &str) -> Vec<String> {
+    words
+        .split(',')
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
 
-    // this works, and returns quite fast
-    fn a_star(&mut self, ignore_words: &Vec<String>) -> Option<Vec<Vec<String>>> {
-        let graph: &HashMap<char, Vec<String>> = &self.start_letter_dictionary;
+fn parse_seed(number: &str) -> Result<u64, String> {
+    number
+        .parse::<u64>()
+        .map_err(|_| format!("--seed must be a whole number, got {}", number))
+}
+
+fn parse_top(count: &str) -> Result<usize, String> {
+    match count.parse::<usize>() {
+        Ok(top) if top >= 1 => Ok(top),
+        _ => Err(format!(
+            "--top must be a number of 1 or more, got {}",
+            count
+        )),
+    }
+}
 
-        let mut priority_queue = BinaryHeap::new();
+fn parse_limit(count: &str) -> Result<usize, String> {
+    match count.parse::<usize>() {
+        Ok(limit) if limit >= 1 => Ok(limit),
+        _ => Err(format!(
+            "--limit must be a number of 1 or more, got {}",
+            count
+        )),
+    }
+}
 
-        let mut solutions: Vec<Vec<String>> = Vec::new();
-        // let mut max_solution_length = 1;
+fn parse_max_rank(rank: &str) -> Result<usize, String> {
+    match rank.parse::<usize>() {
+        Ok(rank) if rank >= 1 => Ok(rank),
+        _ => Err(format!(
+            "--max-rank must be a number of 1 or more, got {}",
+            rank
+        )),
+    }
+}
 
-        // println!("Avaiable chars: {:?}", self.available_chars);
+fn parse_min_word_length(length: &str) -> Result<usize, String> {
+    length
+        .parse::<usize>()
+        .map_err(|_| format!("--min-word-length must be a number, got {}", length))
+}
 
-        // reset priority queue every loop
-        for word in &self.dictionary {
-            // ignore this word if it's in the ignore_words list
-            if ignore_words.contains(word) {
-                continue;
+// the whole run as a single JSON document
+fn solutions_json(
+    groups: &[String],
+    ignore_words: &[String],
+    solutions: &[Solution],
+    frequencies: Option<&Frequencies>,
+    definitions: Option<&Definitions>,
+    explain: Option<&Puzzle>,
+) -> json::Value {
+    let solutions: Vec<json::Value> = solutions
+        .iter()
+        .map(|solution| {
+            let mut result = solution.to_json();
+            if let Some(puzzle) = explain {
+                let steps: Vec<json::Value> = puzzle
+                    .explain(&solution.words)
+                    .iter()
+                    .map(|step| step.to_json())
+                    .collect();
+                result = result.with("explanation", steps);
             }
-            let last_char = word.chars().last().unwrap();
-            priority_queue.push(State {
-                chain: vec![word.clone()],
-                last_word: word.clone(),
-                last_char,
-                cost: 1,
-                heuristic: self.heuristic(&vec![word.clone()], &self.available_chars),
-            });
-        }
-
-        let mut visited = BinaryHeap::new();
-
-        let return_after = 4;
-
-        // find a solution with 1 word, then 2, then 3 etc
-        // this will find the shortest solution
-        for l in 1..=6 {
-            // reset solutions and max solution length
-            solutions = Vec::new();
-            let max_solution_length = l;
-            // priority_queue = BinaryHeap::new();
-
-            while let Some(state) = priority_queue.pop() {
-                // add to visited
-                visited.push(State {
-                    chain: state.chain.clone(),
-                    last_word: state.last_word.clone(),
-                    last_char: state.last_char,
-                    cost: state.cost,
-                    heuristic: state.heuristic,
-                });
-
-                // if it's too long by more than 1, skip it
-                if state.chain.len() > max_solution_length {
-                    continue;
-                }
-                if state.heuristic == 0 {
-                    // return Some(state.chain);
-                    solutions.push(state.chain.clone());
-                    // println!("Solution found: {:?}", state.chain.clone());
-                    // just return the first one found if we're on 4 words
-                    // or if we've got 20 solutions, return those
-                    if max_solution_length > 3 || solutions.len() >= return_after {
-                        return Some(solutions);
-                    }
+            if let Some(frequencies) = frequencies {
+                let rarity = frequencies.rarity(solution);
+                result = result.with("rarity", (rarity != usize::MAX).then_some(rarity));
+            }
+            if let Some(definitions) = definitions {
+                let mut glosses = json::Value::object();
+                for word in &solution.words {
+                    glosses = glosses.with(word, definitions.gloss(word));
                 }
+                result = result.with("definitions", glosses);
+            }
+            result
+        })
+        .collect();
 
-                // println!("Chain: {:?}", state.chain);
-
-                if let Some(next_words) = graph.get(&state.last_char) {
-                    for next_word in next_words {
-                        // don't add the word if it's already in the chain
-                        if state.chain.contains(next_word) {
-                            continue;
-                        }
-                        // if the word is in the ignore_words list, skip it
-                        if ignore_words.contains(next_word) {
-                            continue;
-                        }
-
-                        // println!("\tWord: {}", next_word);
-
-                        let mut new_chain = state.chain.clone();
-                        new_chain.push(next_word.clone());
-
-                        let h = self.heuristic(&new_chain, &self.available_chars);
-
-                        let last_char = next_word.chars().last().unwrap();
-                        priority_queue.push(State {
-                            chain: new_chain.clone(),
-                            last_word: next_word.clone(),
-                            last_char,
-                            cost: state.cost + 1,
-                            heuristic: h,
-                        });
-                    }
-                }
+    json::Value::object()
+        .with("board", groups.to_vec())
+        .with("ignore", ignore_words.to_vec())
+        .with("solutions", solutions)
+}
+
+// the first row of a table of solutions
+fn print_table_header<W: Write>(out: &mut W, separator: char) -> io::Result<()> {
+    let fields = ["board", "word_count", "letter_count", "words"];
+    writeln!(out, "{}", fields.join(&separator.to_string()))
+}
+
+// a row for each solution: the board, how many words and letters it has,
+// and the words separated by spaces
+fn print_table_rows<W: Write>(
+    out: &mut W,
+    separator: char,
+    groups: &[String],
+    solutions: &[Solution],
+) -> io::Result<()> {
+    for solution in solutions {
+        let fields = [
+            groups.join(" "),
+            solution.word_count().to_string(),
+            solution.letter_count.to_string(),
+            solution.words.join(" "),
+        ];
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|field| table_field(separator, field))
+            .collect();
+        writeln!(out, "{}", fields.join(&separator.to_string()))?;
+    }
+    Ok(())
+}
+
+// csv fields with a comma, quote or line break in them are quoted, with
+// quotes doubled. tsv has no quoting, so tabs and line breaks become spaces
+fn table_field(separator: char, field: &str) -> String {
+    if separator == '\t' {
+        field.replace(['\t', '\n', '\r'], " ")
+    } else if field.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// the word lists to use, exiting if there isn't one
+fn dictionary_paths(args: &Args) -> Vec<PathBuf> {
+    if !args.dictionaries.is_empty() {
+        return args.dictionaries.clone();
+    }
+    match lbsolver::find_default_dictionary() {
+        Some(filename) => vec![filename],
+        None => {
+            eprintln!(
+                "Error: could not find {}, use --dictionary to choose a word list",
+                lbsolver::DEFAULT_DICTIONARY
+            );
+            process::exit(EXIT_ERROR);
+        }
+    }
+}
+
+fn dictionary_cache(args: &Args) -> Option<DictionaryCache> {
+    if args.cache {
+        DictionaryCache::user()
+    } else {
+        None
+    }
+}
+
+// the options that leave words out of the word lists
+struct WordRestrictions<'a> {
+    // only the words this common in the frequency list
+    common: Option<(&'a Frequencies, usize)>,
+    // only the words the NYT game accepts
+    accepted: Option<HashSet<String>>,
+    // words taken out of the word lists, whatever else lets them in
+    blocked: HashSet<String>,
+    // words put into the word lists, unless they're blocked
+    added: Vec<String>,
+}
+
+impl WordFilter for WordRestrictions<'_> {
+    fn accept(&self, word: &str) -> bool {
+        !self.blocked.contains(word)
+            && self
+                .common
+                .is_none_or(|(frequencies, max_rank)| frequencies.is_common(word, max_rank))
+            && self
+                .accepted
+                .as_ref()
+                .is_none_or(|accepted| accepted.contains(word))
+    }
+}
+
+impl WordRestrictions<'_> {
+    fn is_empty(&self) -> bool {
+        self.common.is_none() && self.accepted.is_none() && self.blocked.is_empty()
+    }
+
+    // restrict the words, then add the ones that were asked for and aren't
+    // there yet
+    fn apply(&self, words: &mut Vec<String>) {
+        words.retain(|word| self.accept(word));
+        if self.added.is_empty() {
+            return;
+        }
+        let mut known: HashSet<String> = words.iter().cloned().collect();
+        for word in &self.added {
+            if !self.blocked.contains(word) && known.insert(word.clone()) {
+                words.push(word.clone());
             }
+        }
+    }
+}
 
-            if solutions.len() > 0 {
-                // println!("Found {} solutions", solutions.len());
-                // println!("Solutions: {:?}", solutions);
-                break;
-            } else {
-                println!("No solutions found with {} words in the chain", l);
-                // println!("Moving visited to priority queue");
-                // let mut i = 0;
-                while let Some(state) = visited.pop() {
-                    priority_queue.push(state);
-                    // i += 1;
-                }
-                // println!("{} states moved", i);
+// the restrictions asked for on the command line, exiting if the NYT's
+// list can't be read. with --today and no list of its own, the day's words
+// are added once they've been fetched
+fn word_restrictions<'a>(
+    args: &Args,
+    frequencies: Option<&'a Frequencies>,
+) -> WordRestrictions<'a> {
+    let accepted = args
+        .nyt_words
+        .as_ref()
+        .map(|path| match lbsolver::read_word_list(path) {
+            Ok(words) => words.into_iter().collect(),
+            Err(err) => {
+                eprintln!("Error: {} ({})", err, path.display());
+                process::exit(EXIT_ERROR);
+            }
+        });
+    WordRestrictions {
+        common: args
+            .max_rank
+            .zip(frequencies)
+            .map(|(max_rank, frequencies)| (frequencies, max_rank)),
+        accepted,
+        blocked: args.blacklist.iter().cloned().collect(),
+        added: args.whitelist.clone(),
+    }
+}
+
+// every word in the word lists that the restrictions keep, exiting if one
+// can't be read. the cached lists are already lowercased, so they're only
+// used when the dictionary filter doesn't need to see the words as written
+fn read_words(args: &Args, filenames: &[PathBuf], restrictions: &WordRestrictions) -> Vec<String> {
+    let cache = dictionary_cache(args).filter(|_| args.dictionary_filter.is_empty());
+    let lists = filenames.iter().map(|filename| {
+        let words = match &cache {
+            Some(cache) => cache.read_word_list(filename),
+            None => args.dictionary_filter.read_word_list(filename),
+        };
+        match words {
+            Ok(words) => words,
+            Err(err) => {
+                eprintln!("Error: {} ({})", err, filename.display());
+                process::exit(EXIT_ERROR);
             }
         }
+    });
+    let mut words = lbsolver::merge_word_lists(lists);
+    restrictions.apply(&mut words);
+    words
+}
 
-        Some(solutions)
+// a solver for the board, exiting if the word lists can't be read. the
+// words that fit a board are only cached for a single word list, merged
+// or filtered lists, or lists with words added, are put together again
+// each time
+fn open_solver(
+    args: &Args,
+    puzzle: Puzzle,
+    filenames: &[PathBuf],
+    restrictions: &WordRestrictions,
+) -> Solver {
+    let unfiltered = args.dictionary_filter.is_empty() && restrictions.added.is_empty();
+    // without the cache the restrictions are checked as the list is read
+    let (solver, restricted) = match (filenames, dictionary_cache(args)) {
+        ([filename], Some(cache)) if unfiltered => (cache.open_solver(puzzle, filename), false),
+        ([filename], None) if unfiltered => {
+            (Solver::open_with(puzzle, filename, restrictions), true)
+        }
+        _ => {
+            return Solver::from_words(puzzle, read_words(args, filenames, restrictions));
+        }
+    };
+    let mut solver = match solver {
+        Ok(solver) => solver,
+        Err(err) => {
+            eprintln!("Error: {} ({})", err, filenames[0].display());
+            process::exit(EXIT_ERROR);
+        }
+    };
+    if !restricted && !restrictions.is_empty() {
+        solver.retain_words(|word| restrictions.accept(word));
     }
+    solver
+}
 
-    // this is recursive
-    // take the first two letters, and check they are in different
-    // groups, then if there are any letters left, remove the first
-    // letter and recurse
-    fn is_letter_pattern_in_letter_box(letter_groups: &Vec<Vec<char>>, word: &String) -> bool {
-        // get first two characters of word
-        let chars: Vec<char> = word.chars().collect();
-        for i in 0..letter_groups.len() {
-            let group = &letter_groups[i];
-            if group.contains(&chars[0]) && group.contains(&chars[1]) {
-                // if we get here then the first two letters are in the same group
-                // which won't work
-                return false;
+// apply the search options from the command line to a solver
+fn configure(solver: &mut Solver, args: &Args) {
+    solver.set_threads(args.threads);
+    solver.set_algorithm(args.algorithm);
+    solver.set_heuristic(args.heuristic);
+    solver.set_weight(args.weight);
+    if let Some(queue_limit) = args.queue_limit {
+        solver.set_queue_limit(queue_limit);
+    }
+    if let Some(beam_width) = args.beam_width {
+        solver.set_beam_width(beam_width);
+    }
+    solver.set_required_words(&args.required_words);
+    solver.set_start_word(args.start_word.clone());
+    solver.set_max_words(args.max_words);
+    solver.set_min_word_length(args.min_word_length);
+    solver.set_timeout(args.timeout);
+    solver.set_max_nodes(args.max_nodes);
+    // --top picks the best out of a few times as many solutions, unless
+    // it's told how many to find. finding every one can take far longer
+    solver.set_limit(
+        args.limit
+            .or(args.top.map(|top| top.saturating_mul(TOP_CANDIDATES))),
+    );
+    solver.set_all(args.all);
+    // ordering by letters only means anything if every solution with the
+    // fewest words is looked at, not just the first few found, so the
+    // search ranks them all before cutting them down
+    if args.sort == Sort::Letters {
+        solver.set_cost(letters_cost);
+    }
+    if args.progress {
+        solver.set_progress(print_progress);
+    }
+}
+
+// the order of `Solution::cmp_by_letters` as a cost, for chains that all
+// have the same number of words
+fn letters_cost(words: &[&str]) -> u64 {
+    let solution = Solution::new(words.iter().map(|word| word.to_string()).collect());
+    ((solution.letter_count as u64) << 32) | solution.reused_letters() as u64
+}
+
+// progress goes on one line that's rewritten in place on a terminal, and a
+// line at a time otherwise
+fn print_progress(progress: &lbsolver::Progress) {
+    let line = format!(
+        "depth {}: {} chains expanded, {} queued, best coverage {}/{} letters",
+        progress.depth,
+        progress.nodes_expanded,
+        progress.queue_size,
+        progress.best_coverage,
+        progress.letter_count
+    );
+    if io::stderr().is_terminal() {
+        eprint!("\r\x1b[K{}", line);
+    } else {
+        eprintln!("{}", line);
+    }
+}
+
+// clear the progress line once the search is over
+fn clear_progress(args: &Args) {
+    if args.progress && io::stderr().is_terminal() {
+        eprint!("\r\x1b[K");
+    }
+}
+
+// the frequency list if one was asked for, exiting if it can't be read
+fn load_frequencies(args: &Args) -> Option<Frequencies> {
+    let path = args.frequencies.as_ref()?;
+    match Frequencies::open(path) {
+        Ok(frequencies) => Some(frequencies),
+        Err(err) => {
+            eprintln!("Error: {} ({})", err, path.display());
+            process::exit(EXIT_ERROR);
+        }
+    }
+}
+
+// the definitions if they were asked for, exiting if they can't be read
+fn load_definitions(args: &Args) -> Option<Definitions> {
+    let path = args.definitions.as_ref()?;
+    match Definitions::open(path) {
+        Ok(definitions) => Some(definitions),
+        Err(err) => {
+            eprintln!("Error: {} ({})", err, path.display());
+            process::exit(EXIT_ERROR);
+        }
+    }
+}
+
+// a solution, with what each of its words adds for --explain, the board
+// for --draw, and what the words mean underneath when there are
+// definitions to look them up in
+fn print_solution<W: Write>(
+    out: &mut W,
+    args: &Args,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    definitions: Option<&Definitions>,
+) -> io::Result<()> {
+    let chain = if args.color {
+        colored_chain(puzzle, solution)
+    } else {
+        plain_chain(solution)
+    };
+    writeln!(
+        out,
+        "Solution: {} ({} letters)",
+        chain, solution.letter_count
+    )?;
+    if args.explain {
+        let letter_count = puzzle.full_mask().count_ones();
+        for step in puzzle.explain(&solution.words) {
+            let new_letters: Vec<String> = step.new_letters.iter().map(char::to_string).collect();
+            let sides: Vec<String> = step
+                .sides
+                .iter()
+                .map(|side| (side + 1).to_string())
+                .collect();
+            writeln!(
+                out,
+                "    {}: covers {} ({}/{}), sides {}",
+                step.word,
+                if new_letters.is_empty() {
+                    "nothing new".to_string()
+                } else {
+                    new_letters.join(" ")
+                },
+                step.covered,
+                letter_count,
+                sides.join(" > ")
+            )?;
+        }
+    }
+    if args.draw {
+        writeln!(out)?;
+        for line in draw::board(puzzle, solution) {
+            writeln!(out, "{}", line)?;
+        }
+        writeln!(out)?;
+    }
+    if let Some(definitions) = definitions {
+        for word in &solution.words {
+            writeln!(
+                out,
+                "    {}: {}",
+                word,
+                definitions.gloss(word).unwrap_or("(no definition)")
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// what goes between the words of a chain as it's printed
+const CHAIN_ARROW: &str = " \u{2192} ";
+
+// the words of a solution as they're usually printed, TRANQUIL → LOCKJAW,
+// with the letter each word shares with the next in brackets, since there
+// is no color to pick it out
+fn plain_chain(solution: &Solution) -> String {
+    let last = solution.word_count().saturating_sub(1);
+    let words: Vec<String> = solution
+        .words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            word.chars()
+                .enumerate()
+                .map(|(j, c)| {
+                    let c = c.to_uppercase().to_string();
+                    if is_linking_letter(word, i, j, last) {
+                        format!("[{}]", c)
+                    } else {
+                        c
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    words.join(CHAIN_ARROW)
+}
+
+// the same with each letter colored by the side it's on, the letters each
+// word covers for the first time in bold, to show what each word adds to
+// the chain, and the linking letters underlined rather than in brackets.
+// a letter on two sides is colored by the side it's played on
+fn colored_chain(puzzle: &Puzzle, solution: &Solution) -> String {
+    const SIDE_COLORS: [u8; 6] = [31, 32, 33, 34, 35, 36];
+    let last = solution.word_count().saturating_sub(1);
+    let words: Vec<String> = solution
+        .words
+        .iter()
+        .zip(puzzle.played_letters(&solution.words))
+        .enumerate()
+        .map(|(i, (word, letters))| {
+            let mut colored = String::new();
+            for (j, letter) in letters.iter().enumerate() {
+                let color = letter
+                    .side
+                    .map_or(39, |side| SIDE_COLORS[side % SIDE_COLORS.len()]);
+                let bold = if letter.new { "1;" } else { "" };
+                let underline = if is_linking_letter(word, i, j, last) {
+                    "4;"
+                } else {
+                    ""
+                };
+                colored.push_str(&format!(
+                    "\x1b[{}{}{}m{}\x1b[0m",
+                    bold,
+                    underline,
+                    color,
+                    letter.letter.to_uppercase()
+                ));
             }
+            colored
+        })
+        .collect();
+    words.join(CHAIN_ARROW)
+}
+
+// whether the jth letter of the ith word in a chain is the one it shares
+// with the word before or after it
+fn is_linking_letter(word: &str, i: usize, j: usize, last: usize) -> bool {
+    (i > 0 && j == 0) || (i < last && j + 1 == word.chars().count())
+}
+
+// drop repeated solutions and put the rest in the order asked for on the
+// command line
+fn tidy_solutions(args: &Args, solutions: &mut Vec<Solution>, frequencies: Option<&Frequencies>) {
+    lbsolver::dedupe_solutions(solutions, args.ignore_order);
+
+    match (args.sort, frequencies) {
+        (Sort::Search, _) => {}
+        (Sort::Letters, _) => solutions.sort_by(Solution::cmp_by_letters),
+        (Sort::Words, _) => solutions.sort_by(Solution::cmp_by_words),
+        (Sort::Alpha, _) => solutions.sort_by(Solution::cmp_alphabetically),
+        (Sort::Rarity, Some(frequencies)) => {
+            solutions.sort_by(|a, b| frequencies.cmp_by_rarity(a, b))
         }
-        // if we get here then the first two letters are in different groups
-        // get all letters of word minus the first letter
-        let remaining_chars: Vec<char> = chars[1..].to_vec();
-        // we can't check a word with less than 2 characters
-        if remaining_chars.len() == 1 {
-            return true;
+        // serve doesn't load the frequency list, so it keeps the in-game order
+        (Sort::Rarity, None) => solutions.sort_by(Solution::cmp_by_letters),
+    }
+
+    // least common word first, so the most playable solutions come first
+    if let (Some(frequencies), true) = (frequencies, args.common_first) {
+        solutions.sort_by_key(|solution| frequencies.rarity(solution));
+    }
+
+    if let Some(top) = args.top {
+        solutions.truncate(top);
+    }
+}
+
+// make random boards, printing each one with the solution it was made from
+fn generate(args: &Args, words: &[String]) {
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64)
+    });
+    let mut random = Random::new(seed);
+    let generator = Generator::new(words);
+
+    let mut boards = Vec::new();
+    for _ in 0..args.count {
+        match generator.generate(&mut random) {
+            Some(board) => boards.push(board),
+            None => {
+                eprintln!("Error: couldn't find two words to make a board from");
+                process::exit(EXIT_ERROR);
+            }
+        }
+    }
+
+    if args.format == Format::Json {
+        let boards: Vec<json::Value> = boards
+            .into_iter()
+            .map(|(board, solution)| {
+                json::Value::object()
+                    .with("board", board)
+                    .with("solution", solution.words)
+            })
+            .collect();
+        let result = json::Value::object()
+            .with("seed", seed.to_string())
+            .with("boards", boards);
+        println!("{}", result.to_pretty_string());
+        return;
+    }
+
+    // one board per line so they can be fed straight to batch, with the
+    // solution each was made from as a comment when asked for with -v
+    println!("# seed {}", seed);
+    for (board, solution) in boards {
+        println!("{}", board.join(" "));
+        if lbsolver::log::enabled(lbsolver::log::INFO) {
+            println!("# {}", solution.words.join(" "));
+        }
+    }
+}
+
+// check the chain against the rules, printing how it did with each one,
+// returns whether it passed them all
+fn check(args: &Args, words: &[String]) -> bool {
+    let puzzle = match Puzzle::with_group_size(&args.groups, args.group_size) {
+        Ok(puzzle) => puzzle,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_ERROR);
         }
+    };
+    let words: HashSet<&str> = words.iter().map(String::as_str).collect();
+    let checks = puzzle.check(&args.chain, |word| words.contains(word));
+    let valid = checks.iter().all(RuleCheck::passed);
 
-        // send a string of the remaining characters to the function
-        // if it returns true then we have a match
-        return LetterBoxedSolver::is_letter_pattern_in_letter_box(
-            letter_groups,
-            &remaining_chars.iter().collect(),
+    if args.format == Format::Json {
+        let checks: Vec<json::Value> = checks.iter().map(RuleCheck::to_json).collect();
+        let result = json::Value::object()
+            .with("board", args.groups.clone())
+            .with("words", args.chain.clone())
+            .with("valid", valid)
+            .with("rules", checks);
+        println!("{}", result.to_pretty_string());
+        return valid;
+    }
+
+    println!("Checking {:?}", args.chain);
+    for check in &checks {
+        println!(
+            "{} {}",
+            if check.passed() { "PASS" } else { "FAIL" },
+            check.rule
         );
+        for problem in &check.problems {
+            println!("     {}", problem);
+        }
     }
+    println!("Result: {}", if valid { "PASS" } else { "FAIL" });
+    valid
+}
+
+// list the words the spelling bee accepts, returns whether there were any
+fn spelling_bee(args: &Args, words: &[String]) -> bool {
+    let bee = match SpellingBee::new(&args.groups[0], &args.groups[1]) {
+        Ok(bee) => bee,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_ERROR);
+        }
+    };
+    let mut found = bee.words(words);
+    found.retain(|word| !args.ignore_words.contains(&word.word));
+    let score: usize = found.iter().map(|word| word.score).sum();
+    let pangrams = found.iter().filter(|word| word.pangram).count();
+
+    match args.format {
+        Format::Json => {
+            let words: Vec<json::Value> = found.iter().map(BeeWord::to_json).collect();
+            let result = bee
+                .to_json()
+                .with("ignore", args.ignore_words.clone())
+                .with("score", score)
+                .with("pangrams", pangrams)
+                .with("words", words);
+            println!("{}", result.to_pretty_string());
+        }
+        Format::Csv | Format::Tsv => {
+            let separator = args.format.separator().unwrap_or(',');
+            println!(
+                "{}",
+                ["word", "score", "pangram"].join(&separator.to_string())
+            );
+            for word in &found {
+                println!(
+                    "{1}{0}{2}{0}{3}",
+                    separator, word.word, word.score, word.pangram
+                );
+            }
+        }
+        Format::Text => {
+            let others: String = bee
+                .letters()
+                .iter()
+                .filter(|&&c| c != bee.center())
+                .collect();
+            println!("Spelling Bee: {} in the middle of {}", bee.center(), others);
+            println!(
+                "{} words, {} pangrams, {} points\n",
+                found.len(),
+                pangrams,
+                score
+            );
+            for word in &found {
+                let pangram = if word.pangram { "  (pangram)" } else { "" };
+                println!("{:>3}  {}{}", word.score, word.word, pangram);
+            }
+        }
+    }
+    !found.is_empty()
+}
+
+fn print_difficulty(difficulty: &lbsolver::Difficulty) {
+    println!(
+        "Difficulty: {:.1}/10 ({})",
+        difficulty.rating,
+        difficulty.label()
+    );
+    println!("  words that fit the board: {}", difficulty.word_count);
+    println!("  two word solutions: {}", difficulty.two_word_solutions);
+    println!(
+        "  how evenly the words use the letters: {:.2}",
+        difficulty.letter_entropy
+    );
+    if let Some((letter, count)) = difficulty.rarest_letter {
+        println!("  rarest letter: {} ({} words)", letter, count);
+    }
+}
+
+// show the next word to play and how many it takes to finish from there,
+// false if there's no way to finish
+fn hint(args: &Args, solver: &Solver) -> bool {
+    let covered = solver.puzzle().chain_mask(&args.played);
+    let solved = covered == solver.puzzle().full_mask();
+    let finish = if solved {
+        None
+    } else {
+        solver.hint(&args.played, &args.ignore_words)
+    };
+
+    if args.format == Format::Json {
+        let hint = json::Value::object()
+            .with("board", args.groups.clone())
+            .with("played", args.played.clone())
+            .with("solved", solved)
+            .with(
+                "hint",
+                finish.as_ref().map(|finish| finish.words[0].clone()),
+            )
+            .with("words_to_finish", finish.as_ref().map(Solution::word_count));
+        println!("{}", hint.to_pretty_string());
+        return solved || finish.is_some();
+    }
+
+    let finishes = solved || finish.is_some();
+    match finish {
+        _ if solved => println!("Every letter is covered already, the board is solved"),
+        Some(finish) => println!(
+            "Hint: {} (finishes in {} {})",
+            finish.words[0],
+            finish.word_count(),
+            if finish.word_count() == 1 {
+                "word"
+            } else {
+                "words"
+            }
+        ),
+        None => println!(
+            "No way to finish in {} more words from here",
+            args.max_words
+        ),
+    }
+    finishes
 }
 
 fn main() {
-    println!("Starting Letter Boxed Solver...");
+    if args_os().skip(1).any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    if args_os().skip(1).any(|arg| arg == "--schema") {
+        println!("{}", schema::SOLVE_RESULT);
+        return;
+    }
+    let mut args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("Error: {}\n", err);
+            eprintln!("{}", USAGE);
+            process::exit(EXIT_ERROR);
+        }
+    };
+
+    lbsolver::log::set_level(args.verbosity);
+
+    // gen's boards are kept on their own so they can go straight to batch
+    if let Some(edit) = &args.ignore_edit {
+        if let Err(err) = ignored::run(edit) {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_ERROR);
+        }
+        return;
+    }
+    if let Some(DictAction::Compile { input, output }) = &args.dict_action {
+        if let Err(err) = dict::compile(input, output) {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_ERROR);
+        }
+        return;
+    }
 
-    let filename: String = "./yawl_mendel_lee_cooper_word-list-for-lb.txt".to_string();
-    let file = File::open(filename);
+    if args.format == Format::Text
+        && !args.quiet
+        && !matches!(args.command, Command::Gen | Command::Bee | Command::Dict)
+    {
+        println!("Starting Letter Boxed Solver...");
+    }
+
+    let filenames = dictionary_paths(&args);
+    let frequencies = load_frequencies(&args);
+    let mut restrictions = word_restrictions(&args, frequencies.as_ref());
+
+    if let Some(path) = &args.batch_file {
+        let dictionary = Dictionary::from_words(read_words(&args, &filenames, &restrictions));
+        let definitions = load_definitions(&args);
+        let (frequencies, definitions) = (frequencies.as_ref(), definitions.as_ref());
+        let mut source: Box<dyn io::BufRead> = if path.as_os_str() == "-" {
+            Box::new(io::stdin().lock())
+        } else {
+            match File::open(path) {
+                Ok(file) => Box::new(BufReader::new(file)),
+                Err(err) => {
+                    eprintln!("Error: {} ({})", err, path.display());
+                    process::exit(EXIT_ERROR);
+                }
+            }
+        };
+        let written = write_output(&args, |out| {
+            batch::run(
+                out,
+                &args,
+                &dictionary,
+                frequencies,
+                definitions,
+                &mut source,
+            )
+        });
+        if !written {
+            process::exit(EXIT_ERROR);
+        }
+        return;
+    }
 
-    let args_count: usize = args_os().count();
+    if args.command == Command::Repl || args.command == Command::Serve {
+        let dictionary = Dictionary::from_words(read_words(&args, &filenames, &restrictions));
+        if args.command == Command::Repl {
+            repl::run(&args, &dictionary);
+        } else if let Err(err) = serve::run(args, dictionary) {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_ERROR);
+        }
+        return;
+    }
 
-    if args_count < 5 {
-        println!("Usage: lbsolver <group1> <group2> <group3> <group4> <ignore_word (opt)> <ignore_word (opt)> ...");
-        println!("Each group must be 3 letters long");
-        println!("Any words after the 4 groups of 3 letters will be filtered out in the searching");
+    if args.command == Command::Bench {
+        if let Err(err) = bench::run(&args, &filenames, &restrictions, &args.bench_boards) {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_ERROR);
+        }
         return;
     }
 
-    let args = args_os().into_iter().collect::<Vec<_>>();
+    if args.command == Command::Gen {
+        let words = read_words(&args, &filenames, &restrictions);
+        generate(&args, &words);
+        return;
+    }
 
-    // turn args into Vec<String>
-    let args_string: Vec<String> = args
-        .iter()
-        .map(|arg| arg.to_owned().into_string().unwrap())
-        .collect();
+    if let Some(DictAction::Stats { board }) = &args.dict_action {
+        let words = read_words(&args, &filenames, &restrictions);
+        if let Err(err) = dict::stats(&args, board, &words) {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_ERROR);
+        }
+        return;
+    }
+    if args.command == Command::Bee {
+        let words = read_words(&args, &filenames, &restrictions);
+        if !spelling_bee(&args, &words) {
+            process::exit(EXIT_NO_SOLUTION);
+        }
+        return;
+    }
+
+    if args.command == Command::Check {
+        let words = read_words(&args, &filenames, &restrictions);
+        if !check(&args, &words) {
+            process::exit(EXIT_NO_SOLUTION);
+        }
+        return;
+    }
+
+    let mut todays_words = None;
+    if args.today {
+        match today::fetch() {
+            Ok(todays_puzzle) => {
+                args.groups = todays_puzzle.sides;
+                args.group_size = args.groups.first().map_or(0, |side| side.chars().count());
+                todays_words = todays_puzzle.dictionary;
+            }
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                process::exit(EXIT_ERROR);
+            }
+        }
+    }
+
+    let puzzle = match Puzzle::with_group_size(&args.groups, args.group_size) {
+        Ok(puzzle) => puzzle,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_ERROR);
+        }
+    };
+    if args.strict_nyt && restrictions.accepted.is_none() {
+        match &todays_words {
+            Some(words) => {
+                restrictions.accepted =
+                    Some(words.iter().map(|word| word.to_lowercase()).collect());
+            }
+            None => {
+                eprintln!(
+                    "Error: the NYT site didn't give the words it accepts today, use --nyt-words"
+                );
+                process::exit(EXIT_ERROR);
+            }
+        }
+    }
+    // the NYT's own word list is the best one to use, unless one was asked for
+    let loading = Instant::now();
+    let mut solver = match todays_words {
+        Some(mut words) if args.dictionaries.is_empty() => {
+            restrictions.apply(&mut words);
+            Solver::from_words(puzzle, words)
+        }
+        _ => open_solver(&args, puzzle, &filenames, &restrictions),
+    };
 
-    let groups: Vec<String> = args_string[1..5].to_vec();
+    let definitions = load_definitions(&args);
 
-    let ignore_words: Vec<String> = args_string[5..].to_vec();
+    configure(&mut solver, &args);
+    let loading_time = loading.elapsed();
 
-    let mut solver = LetterBoxedSolver::new(&groups, file.unwrap());
+    if args.command == Command::Difficulty {
+        let difficulty = solver.difficulty();
+        if args.format == Format::Json {
+            let result = json::Value::object()
+                .with("board", args.groups.clone())
+                .with("difficulty", difficulty.to_json());
+            println!("{}", result.to_pretty_string());
+        } else {
+            print_difficulty(&difficulty);
+        }
+        return;
+    }
 
-    let found_solutions = solver.run_solver(&ignore_words);
-    println!("Groups: {:?}", groups);
-    println!("Ignore: {:?}", ignore_words);
+    if args.command == Command::Hint {
+        if !hint(&args, &solver) {
+            process::exit(EXIT_NO_SOLUTION);
+        }
+        return;
+    }
+
+    if args.command == Command::Tui {
+        if let Err(err) = tui::run(&args, &solver) {
+            eprintln!("Error: {}", err);
+            process::exit(EXIT_ERROR);
+        }
+        return;
+    }
+
+    let started = Instant::now();
+    solver.set_checkpoint(args.checkpoint.clone());
+    let (mut found_solutions, search_stats) = if args.pairs {
+        let pairs = solver.pairs(&args.ignore_words);
+        let stats = SearchStats {
+            joined: true,
+            search_time: started.elapsed(),
+            ..SearchStats::default()
+        };
+        (Ok(pairs), stats)
+    } else if let Some(path) = &args.resume {
+        match solver.resume_solver_with_stats(path, &args.ignore_words) {
+            (Err(err @ LbError::InvalidCheckpoint(_)), _) => {
+                eprintln!("Error: {}", err);
+                process::exit(EXIT_ERROR);
+            }
+            found => found,
+        }
+    } else {
+        solver.run_solver_with_stats(&args.ignore_words)
+    };
+    let stats = args.stats.then(|| RunStats {
+        search: search_stats,
+        words: solver.dictionary().len(),
+        loading_time,
+    });
+    clear_progress(&args);
+    let timed_out = args
+        .timeout
+        .is_some_and(|timeout| started.elapsed() >= timeout);
+    // nothing else stops the search early from the command line
+    let out_of_nodes = search_stats.stopped && !timed_out;
+
+    // the search doesn't print anything itself, so say which chain
+    // lengths came up empty before the ones that worked. a search that
+    // ran out of time without finding any can't say how long they are
+    if let (false, false, Ok(solutions)) = (args.quiet, args.pairs, &found_solutions) {
+        let best = match solutions.first() {
+            Some(solution) => solution.word_count(),
+            None if timed_out || out_of_nodes => 1,
+            None => args.max_words + 1,
+        };
+        for l in 1..best {
+            eprintln!("No solutions found with {} words in the chain", l);
+        }
+    }
+    if args.quiet {
+        // nothing but the solutions, even when they might not be the best
+    } else if timed_out {
+        eprintln!(
+            "Stopped searching after {:.1?}, these are the best solutions found by then",
+            started.elapsed()
+        );
+    } else if out_of_nodes {
+        eprintln!(
+            "Stopped searching after expanding {} chains, these are the best solutions found by then",
+            search_stats.nodes_expanded
+        );
+    }
+
+    if let Ok(solutions) = &mut found_solutions {
+        tidy_solutions(&args, solutions, frequencies.as_ref());
+    }
+    let exit_code = match &found_solutions {
+        Ok(solutions) if !solutions.is_empty() => 0,
+        _ => EXIT_NO_SOLUTION,
+    };
+
+    // the board is still drawn when there's no solution to draw on it
+    if let Some(path) = &args.svg {
+        let first = found_solutions
+            .as_ref()
+            .ok()
+            .and_then(|solutions| solutions.first());
+        if let Err(err) = fs::write(path, draw::svg(solver.puzzle(), first)) {
+            eprintln!("Error: could not write {} ({})", path.display(), err);
+            process::exit(EXIT_ERROR);
+        }
+    }
+
+    let written = write_output(&args, |out| {
+        write_solutions(
+            out,
+            &args,
+            solver.puzzle(),
+            found_solutions,
+            frequencies.as_ref(),
+            definitions.as_ref(),
+            stats.as_ref(),
+        )
+    });
+    if !written {
+        process::exit(EXIT_ERROR);
+    }
+    if let (Some(stats), false) = (&stats, args.format == Format::Json) {
+        stats.print();
+    }
+    process::exit(exit_code);
+}
+
+// what --stats reports: the search's own numbers, with the words that fit
+// the board and how long it took to read them
+struct RunStats {
+    search: SearchStats,
+    words: usize,
+    loading_time: Duration,
+}
+
+impl RunStats {
+    // on stderr, out of the way of the solutions
+    fn print(&self) {
+        let search = &self.search;
+        eprintln!("\nSearch stats:");
+        eprintln!("  words that fit the board: {}", self.words);
+        if search.joined {
+            eprintln!(
+                "  the solutions were joined straight from the word lists, nothing was searched"
+            );
+        } else {
+            eprintln!("  chains expanded: {}", search.nodes_expanded);
+            eprintln!("  chains pruned: {}", search.nodes_pruned);
+            eprintln!("  most chains waiting at once: {}", search.peak_queue_size);
+        }
+        eprintln!("  reading the words: {:.1?}", self.loading_time);
+        eprintln!("  searching: {:.1?}", search.search_time);
+        eprintln!("  ranking: {:.1?}", search.ranking_time);
+    }
+
+    fn to_json(&self) -> json::Value {
+        // in seconds, to the millisecond
+        let seconds = |time: Duration| (time.as_secs_f64() * 1000.0).round() / 1000.0;
+        let search = &self.search;
+        json::Value::object()
+            .with("words", self.words)
+            .with("joined", search.joined)
+            .with("nodes_expanded", search.nodes_expanded)
+            .with("nodes_pruned", search.nodes_pruned)
+            .with("peak_queue_size", search.peak_queue_size)
+            .with(
+                "seconds",
+                json::Value::object()
+                    .with("reading", seconds(self.loading_time))
+                    .with("searching", seconds(search.search_time))
+                    .with("ranking", seconds(search.ranking_time)),
+            )
+    }
+}
+
+// write to stdout, or the --output file, saying what went wrong if it
+// couldn't be written. the file is only put in place when everything
+// was written
+fn write_output<F>(args: &Args, write: F) -> bool
+where
+    F: FnOnce(&mut Output) -> io::Result<()>,
+{
+    let name = args
+        .output
+        .as_ref()
+        .map_or("the output".to_string(), |path| path.display().to_string());
+    let written =
+        Output::create(args.output.as_deref()).and_then(|mut out| match write(&mut out) {
+            Ok(()) => out.finish(),
+            Err(err) => {
+                out.abandon();
+                Err(err)
+            }
+        });
+    if let Err(err) = &written {
+        eprintln!("Error: could not write {} ({})", name, err);
+    }
+    written.is_ok()
+}
+
+fn write_solutions<W: Write>(
+    out: &mut W,
+    args: &Args,
+    puzzle: &Puzzle,
+    found_solutions: Result<Vec<Solution>, LbError>,
+    frequencies: Option<&Frequencies>,
+    definitions: Option<&Definitions>,
+    stats: Option<&RunStats>,
+) -> io::Result<()> {
+    if let Some(separator) = args.format.separator() {
+        print_table_header(out, separator)?;
+        return print_table_rows(
+            out,
+            separator,
+            &args.groups,
+            &found_solutions.unwrap_or_default(),
+        );
+    }
+
+    if args.format == Format::Json {
+        let solutions = found_solutions.unwrap_or_default();
+        let mut result = solutions_json(
+            &args.groups,
+            &args.ignore_words,
+            &solutions,
+            frequencies,
+            definitions,
+            args.explain.then_some(puzzle),
+        );
+        if let Some(stats) = stats {
+            result = result.with("stats", stats.to_json());
+        }
+        return writeln!(out, "{}", result.to_pretty_string());
+    }
+
+    if args.quiet {
+        for solution in found_solutions.unwrap_or_default() {
+            print_solution(out, args, puzzle, &solution, definitions)?;
+        }
+        return Ok(());
+    }
 
-    if found_solutions.is_ok() {
-        let solutions = found_solutions.unwrap();
+    writeln!(out, "Groups: {:?}", args.groups)?;
+    writeln!(out, "Ignore: {:?}", args.ignore_words)?;
 
-        println!("\n{} solutions found\n", solutions.len());
+    if let Ok(solutions) = found_solutions {
+        writeln!(out, "\n{} solutions found\n", solutions.len())?;
 
         for solution in solutions {
-            println!("Solution: {:?}", solution);
+            print_solution(out, args, puzzle, &solution, definitions)?;
         }
     } else {
-        println!("No solution found");
+        writeln!(out, "No solution found")?;
     }
+    Ok(())
 }
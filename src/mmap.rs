@@ -0,0 +1,121 @@
+// a file mapped into memory read only, so a compiled word list is used
+// where it sits in the page cache instead of being copied onto the heap.
+// std has no mmap, but libc is always linked on unix so it's called
+// directly. anywhere else, or if mapping fails, the file is just read
+//
+// the file shouldn't be cut short while it's mapped. compiled word lists
+// are written to one side and renamed into place, which leaves the mapped
+// copy alone
+
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+
+#[cfg(all(unix, target_pointer_width = "64"))]
+mod sys {
+    use std::ffi::{c_int, c_void};
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::AsRawFd;
+    use std::ptr;
+
+    const PROT_READ: c_int = 1;
+    const MAP_PRIVATE: c_int = 2;
+
+    extern "C" {
+        fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: i64,
+        ) -> *mut c_void;
+        fn munmap(addr: *mut c_void, len: usize) -> c_int;
+    }
+
+    pub fn map(file: &File, len: usize) -> io::Result<*const u8> {
+        // SAFETY: a fresh private read only mapping of an open file, which
+        // nothing else can write through
+        let addr = unsafe {
+            mmap(
+                ptr::null_mut(),
+                len,
+                PROT_READ,
+                MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if addr as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(addr as *const u8)
+    }
+
+    pub fn unmap(addr: *const u8, len: usize) {
+        // SAFETY: the mapping `map` made, unmapped once when it's dropped
+        unsafe {
+            munmap(addr as *mut c_void, len);
+        }
+    }
+}
+
+#[cfg(not(all(unix, target_pointer_width = "64")))]
+mod sys {
+    use std::fs::File;
+    use std::io;
+
+    pub fn map(_file: &File, _len: usize) -> io::Result<*const u8> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "files can't be mapped here",
+        ))
+    }
+
+    pub fn unmap(_addr: *const u8, _len: usize) {}
+}
+
+#[derive(Debug)]
+pub(crate) struct Mapping {
+    addr: *const u8,
+    len: usize,
+}
+
+// SAFETY: the mapping is read only and owned by this, so it can be read
+// from any thread
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    // empty files can't be mapped, so they're an error too and get read
+    // the ordinary way
+    pub(crate) fn open(file: &File) -> io::Result<Mapping> {
+        let len = usize::try_from(file.metadata()?.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "the file is too big"))?;
+        if len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the file is empty",
+            ));
+        }
+        let addr = sys::map(file, len)?;
+        Ok(Mapping { addr, len })
+    }
+}
+
+impl Deref for Mapping {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: `len` bytes were mapped at `addr` and stay mapped until
+        // this is dropped
+        unsafe { std::slice::from_raw_parts(self.addr, self.len) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        sys::unmap(self.addr, self.len);
+    }
+}
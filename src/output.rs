@@ -0,0 +1,85 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+// where the solutions are written: stdout, or with --output a file. the
+// file is written under a temporary name next to it and only renamed into
+// place once everything's in it, so whatever reads it never sees half the
+// output, and a failed run leaves the last good file alone
+pub enum Output {
+    Stdout(io::Stdout),
+    File {
+        file: BufWriter<File>,
+        temporary: PathBuf,
+        path: PathBuf,
+    },
+}
+
+impl Output {
+    pub fn create(path: Option<&Path>) -> io::Result<Output> {
+        let path = match path {
+            Some(path) => path,
+            None => return Ok(Output::Stdout(io::stdout())),
+        };
+        // in the same directory, so the rename can't cross file systems
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{}.tmp", process::id()));
+        let temporary = path.with_file_name(name);
+        let file = BufWriter::new(File::create(&temporary)?);
+        Ok(Output::File {
+            file,
+            temporary,
+            path: path.to_path_buf(),
+        })
+    }
+
+    // put the file in place, once there's nothing more to write
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Output::Stdout(mut stdout) => stdout.flush(),
+            Output::File {
+                file,
+                temporary,
+                path,
+            } => {
+                let written = file
+                    .into_inner()
+                    .map_err(io::IntoInnerError::into_error)
+                    .and_then(|file| file.sync_all())
+                    .and_then(|_| fs::rename(&temporary, &path));
+                if written.is_err() {
+                    let _ = fs::remove_file(&temporary);
+                }
+                written
+            }
+        }
+    }
+
+    // throw away what's been written, when the run failed part way
+    pub fn abandon(self) {
+        if let Output::File {
+            file, temporary, ..
+        } = self
+        {
+            drop(file);
+            let _ = fs::remove_file(temporary);
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Stdout(stdout) => stdout.write(buf),
+            Output::File { file, .. } => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Stdout(stdout) => stdout.flush(),
+            Output::File { file, .. } => file.flush(),
+        }
+    }
+}
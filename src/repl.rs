@@ -0,0 +1,121 @@
+use std::io::{self, BufRead, Write};
+
+use lbsolver::{Dictionary, Puzzle, Solution, Solutions, Solver};
+
+use crate::{configure, plain_chain, Args};
+
+const HELP: &str = "Commands:
+  board <group> <group> ...  set the board to solve, e.g. board abc def ghi jkl
+  ignore <word> ...          leave words out of the solutions
+  unignore <word> ...        put ignored words back
+  solve                      show the best solutions for the board
+  more [n]                   show the next n solutions (default: 5)
+  pairs                      show every two word solution
+  help                       show this message
+  quit                       leave the repl";
+
+fn prompt() {
+    print!("> ");
+    let _ = io::stdout().flush();
+}
+
+fn print_solutions(solutions: &[Solution]) {
+    println!("{} solutions found", solutions.len());
+    for solution in solutions {
+        println!(
+            "Solution: {} ({} letters)",
+            plain_chain(solution),
+            solution.letter_count
+        );
+    }
+}
+
+// read commands from stdin until it closes or the user quits, keeping the
+// word list in memory so each new board only has to be filtered
+pub fn run(args: &Args, dictionary: &Dictionary) {
+    let mut solver: Option<Solver> = None;
+    // the search `more` carries on from, restarted when anything changes
+    let mut search: Option<Solutions> = None;
+    let mut ignore_words: Vec<String> = args.ignore_words.clone();
+
+    println!("Loaded {} words, type help for commands", dictionary.len());
+    prompt();
+
+    for line in io::stdin().lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+        let mut parts = line.split_whitespace();
+        let command = match parts.next() {
+            Some(command) => command,
+            None => {
+                prompt();
+                continue;
+            }
+        };
+        let rest: Vec<String> = parts.map(str::to_string).collect();
+
+        match command {
+            "board" => {
+                search = None;
+                match Puzzle::new(&rest) {
+                    Ok(puzzle) => {
+                        let mut new_solver = dictionary.solver_for(&puzzle);
+                        configure(&mut new_solver, args);
+                        println!("{} words fit the board", new_solver.dictionary().len());
+                        solver = Some(new_solver);
+                    }
+                    Err(err) => println!("Error: {}", err),
+                }
+            }
+            "ignore" => {
+                search = None;
+                ignore_words.extend(rest);
+                println!("Ignore: {:?}", ignore_words);
+            }
+            "unignore" => {
+                search = None;
+                ignore_words.retain(|word| !rest.contains(word));
+                println!("Ignore: {:?}", ignore_words);
+            }
+            "solve" => {
+                search = None;
+                match solver.as_mut() {
+                    Some(solver) => match solver.run_solver(&ignore_words) {
+                        Ok(solutions) => print_solutions(&solutions),
+                        Err(err) => println!("Error: {}", err),
+                    },
+                    None => println!("Set a board first, e.g. board abc def ghi jkl"),
+                }
+            }
+            "pairs" | "more" => {
+                let solver = match &solver {
+                    Some(solver) => solver,
+                    None => {
+                        println!("Set a board first, e.g. board abc def ghi jkl");
+                        prompt();
+                        continue;
+                    }
+                };
+                match command {
+                    "pairs" => print_solutions(&solver.pairs(&ignore_words)),
+                    _ => {
+                        let count = rest.first().and_then(|n| n.parse().ok()).unwrap_or(5);
+                        let search = search.get_or_insert_with(|| solver.solutions(&ignore_words));
+                        let more: Vec<Solution> = search.take(count).collect();
+                        if more.is_empty() {
+                            println!("No more solutions");
+                        } else {
+                            print_solutions(&more);
+                        }
+                    }
+                }
+            }
+            "help" => println!("{}", HELP),
+            "quit" | "exit" => break,
+            _ => println!("Unknown command {}, type help for commands", command),
+        }
+        prompt();
+    }
+}
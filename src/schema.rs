@@ -0,0 +1,122 @@
+// the JSON Schema for what --format json prints for a board: the whole
+// document for solve, and each line of batch's output. the fields that
+// are only there with --explain, --frequencies, --definitions or --stats
+// aren't required, and batch writes an error in place of the solutions when a
+// board can't be solved
+pub const SOLVE_RESULT: &str = r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": "lbsolver solve result",
+  "type": "object",
+  "properties": {
+    "board": {
+      "description": "the groups of letters, one string per side",
+      "type": "array",
+      "items": { "type": "string" }
+    },
+    "ignore": {
+      "description": "the words left out of the search",
+      "type": "array",
+      "items": { "type": "string" }
+    },
+    "solutions": {
+      "description": "the solutions found, best first",
+      "type": "array",
+      "items": { "$ref": "#/$defs/solution" }
+    },
+    "error": {
+      "description": "why the board couldn't be solved, batch only",
+      "type": "string"
+    },
+    "stats": { "$ref": "#/$defs/stats" }
+  },
+  "required": ["board", "ignore"],
+  "oneOf": [
+    { "required": ["solutions"] },
+    { "required": ["error"] }
+  ],
+  "$defs": {
+    "solution": {
+      "type": "object",
+      "properties": {
+        "words": {
+          "type": "array",
+          "items": { "type": "string" },
+          "minItems": 1
+        },
+        "word_count": { "type": "integer", "minimum": 1 },
+        "letter_count": {
+          "description": "the letters across all the words",
+          "type": "integer",
+          "minimum": 0
+        },
+        "explanation": {
+          "description": "what each word adds, with --explain",
+          "type": "array",
+          "items": { "$ref": "#/$defs/step" }
+        },
+        "rarity": {
+          "description": "the frequency rank of the least common word, null when one isn't in the list, with --frequencies",
+          "type": ["integer", "null"],
+          "minimum": 0
+        },
+        "definitions": {
+          "description": "what each word means, null when it isn't known, with --definitions",
+          "type": "object",
+          "additionalProperties": { "type": ["string", "null"] }
+        }
+      },
+      "required": ["words", "word_count", "letter_count"]
+    },
+    "step": {
+      "type": "object",
+      "properties": {
+        "word": { "type": "string" },
+        "new_letters": {
+          "description": "the letters the word covers first",
+          "type": "array",
+          "items": { "type": "string", "minLength": 1, "maxLength": 1 }
+        },
+        "sides": {
+          "description": "the side each letter is on, counting from 0",
+          "type": "array",
+          "items": { "type": "integer", "minimum": 0 }
+        },
+        "covered": {
+          "description": "how many of the board's letters the chain has covered after the word",
+          "type": "integer",
+          "minimum": 0
+        }
+      },
+      "required": ["word", "new_letters", "sides", "covered"]
+    },
+    "stats": {
+      "description": "how much searching it took, with --stats",
+      "type": "object",
+      "properties": {
+        "words": {
+          "description": "the words that fit the board",
+          "type": "integer",
+          "minimum": 0
+        },
+        "joined": {
+          "description": "whether the solutions were joined straight from the word lists without a search",
+          "type": "boolean"
+        },
+        "nodes_expanded": { "type": "integer", "minimum": 0 },
+        "nodes_pruned": { "type": "integer", "minimum": 0 },
+        "peak_queue_size": { "type": "integer", "minimum": 0 },
+        "seconds": {
+          "description": "how long each step took",
+          "type": "object",
+          "properties": {
+            "reading": { "type": "number", "minimum": 0 },
+            "searching": { "type": "number", "minimum": 0 },
+            "ranking": { "type": "number", "minimum": 0 }
+          },
+          "required": ["reading", "searching", "ranking"]
+        }
+      },
+      "required": ["words", "joined", "nodes_expanded", "nodes_pruned", "peak_queue_size", "seconds"]
+    }
+  }
+}"##;
@@ -0,0 +1,272 @@
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use lbsolver::{json, Dictionary, Puzzle, Solution, Solver};
+
+use crate::{configure, tidy_solutions, Args};
+
+// the biggest request body that will be read
+const MAX_BODY: usize = 64 * 1024;
+
+// how long a client has to send its request, or take the response,
+// before the connection is dropped
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+// the most requests handled at once, others are turned away with a 503
+const MAX_CONNECTIONS: usize = 32;
+
+// how many chains a request can expand when the server is started without
+// --max-nodes or --timeout, so no request can search forever
+const DEFAULT_MAX_NODES: usize = 10_000_000;
+
+fn error(message: &str) -> json::Value {
+    json::Value::object().with("error", message)
+}
+
+// answer POST /solve on the port until the process is killed, keeping the
+// word list in memory so each request only has to filter it for its board.
+// POST /solve/stream takes the same requests, and writes each solution as
+// a line of JSON as soon as the search finds it
+pub fn run(args: Args, dictionary: Dictionary) -> io::Result<()> {
+    let listener = TcpListener::bind((args.host.as_str(), args.port))?;
+    eprintln!(
+        "Listening on {}:{}, POST /solve with {{\"sides\": [...], \"ignore\": [...], \"max_words\": n}}",
+        args.host, args.port
+    );
+
+    // a thread per connection, so a slow board doesn't hold up the rest,
+    // up to MAX_CONNECTIONS of them
+    let shared = Arc::new((args, dictionary));
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        if stream.set_read_timeout(Some(IO_TIMEOUT)).is_err()
+            || stream.set_write_timeout(Some(IO_TIMEOUT)).is_err()
+        {
+            continue;
+        }
+        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            active.fetch_sub(1, Ordering::SeqCst);
+            let _ = respond(
+                &mut stream,
+                "503 Service Unavailable",
+                &error("too busy, try again"),
+            );
+            continue;
+        }
+        let shared = Arc::clone(&shared);
+        let active = Arc::clone(&active);
+        thread::spawn(move || {
+            let (args, dictionary) = &*shared;
+            if let Err(err) = handle(stream, args, dictionary) {
+                eprintln!("Error: {}", err);
+            }
+            active.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+    Ok(())
+}
+
+// the most chains a request can expand: --max-nodes, or DEFAULT_MAX_NODES
+// when neither it nor --timeout bounds the search
+fn node_limit(args: &Args) -> Option<usize> {
+    args.max_nodes
+        .or(args.timeout.is_none().then_some(DEFAULT_MAX_NODES))
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &json::Value) -> io::Result<()> {
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+// read one request and write its response, a connection per request
+fn handle(mut stream: TcpStream, args: &Args, dictionary: &Dictionary) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // the only header that matters is how long the body is
+    let mut content_length: usize = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+    let (status, body) = if path != "/solve" && path != "/solve/stream" {
+        ("404 Not Found", error("not found, POST boards to /solve"))
+    } else if method != "POST" {
+        ("405 Method Not Allowed", error("use POST to solve a board"))
+    } else if content_length > MAX_BODY {
+        ("413 Payload Too Large", error("the request is too big"))
+    } else {
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        match parse_request(args, dictionary, &String::from_utf8_lossy(&body)) {
+            Ok(request) if path == "/solve/stream" => return stream_solutions(stream, request),
+            Ok(request) => match solve(args, request) {
+                Ok(solutions) => ("200 OK", solutions),
+                Err(err) => ("400 Bad Request", error(&err)),
+            },
+            Err(err) => ("400 Bad Request", error(&err)),
+        }
+    };
+
+    respond(&mut stream, status, &body)
+}
+
+// a request's board, its words to ignore, and a solver set up for it
+struct Request {
+    sides: Vec<String>,
+    ignore_words: Vec<String>,
+    solver: Solver,
+}
+
+// read a request like {"sides": ["abc", "def", "ghi", "jkl"], "ignore":
+// ["word"], "max_words": 3}, the command line options are the defaults for
+// anything it leaves out. the board can be given as a string instead,
+// {"board": "abc-def-ghi-jkl"}, and "max_nodes" limits how much searching
+// the request does. "max_words" and "max_nodes" can lower --max-words and
+// the server's node limit but not raise them, so those still hold whatever
+// a client asks for
+fn parse_request(args: &Args, dictionary: &Dictionary, body: &str) -> Result<Request, String> {
+    let request = json::parse(body).map_err(|err| err.to_string())?;
+    let sides: Vec<String> = match request.get("board").and_then(json::Value::as_str) {
+        Some(board) => {
+            let puzzle = board.parse::<Puzzle>().map_err(|err| err.to_string())?;
+            puzzle
+                .letter_groups()
+                .iter()
+                .map(|side| side.iter().collect())
+                .collect()
+        }
+        None => request
+            .get("sides")
+            .and_then(json::Value::as_strings)
+            .ok_or("the request needs sides, a list of groups of letters")?
+            .iter()
+            .map(|side| side.to_lowercase())
+            .collect(),
+    };
+
+    let mut ignore_words = args.ignore_words.clone();
+    if let Some(ignore) = request.get("ignore") {
+        let ignore = ignore
+            .as_strings()
+            .ok_or("ignore must be a list of words")?;
+        ignore_words.extend(ignore.iter().map(|word| word.to_lowercase()));
+    }
+
+    let puzzle = Puzzle::new(&sides).map_err(|err| err.to_string())?;
+    let mut solver = dictionary.solver_for(&puzzle);
+    configure(&mut solver, args);
+    solver.set_max_nodes(node_limit(args));
+    if let Some(max_words) = request.get("max_words") {
+        let max_words = max_words
+            .as_f64()
+            .filter(|&max_words| max_words >= 1.0)
+            .ok_or("max_words must be a number of 1 or more")?;
+        if max_words as usize > args.max_words {
+            return Err(format!("max_words can be at most {}", args.max_words));
+        }
+        solver.set_max_words(max_words as usize);
+    }
+    if let Some(max_nodes) = request.get("max_nodes") {
+        let max_nodes = max_nodes
+            .as_f64()
+            .filter(|&max_nodes| max_nodes >= 1.0)
+            .ok_or("max_nodes must be a number of 1 or more")?;
+        let max_nodes = max_nodes as usize;
+        if let Some(limit) = node_limit(args).filter(|&limit| max_nodes > limit) {
+            return Err(format!("max_nodes can be at most {}", limit));
+        }
+        solver.set_max_nodes(Some(max_nodes));
+    }
+    Ok(Request {
+        sides,
+        ignore_words,
+        solver,
+    })
+}
+
+fn solve(args: &Args, request: Request) -> Result<json::Value, String> {
+    let Request {
+        sides,
+        ignore_words,
+        solver,
+    } = request;
+    let mut solutions = solver
+        .run_solver(&ignore_words)
+        .map_err(|err| err.to_string())?;
+    tidy_solutions(args, &mut solutions, None);
+    let solutions: Vec<json::Value> = solutions.iter().map(Solution::to_json).collect();
+    Ok(json::Value::object()
+        .with("sides", sides)
+        .with("ignore", ignore_words)
+        .with("solutions", solutions))
+}
+
+// write the solutions as they're found, a line of JSON each, in the order
+// the search finds them rather than sorted, as many as /solve would give.
+// the response has no length, it ends when the connection is closed
+fn stream_solutions(mut stream: TcpStream, request: Request) -> io::Result<()> {
+    let Request {
+        sides,
+        ignore_words,
+        solver,
+    } = request;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\n\r\n"
+    )?;
+    writeln!(
+        stream,
+        "{}",
+        json::Value::object()
+            .with("sides", sides)
+            .with("ignore", ignore_words.clone())
+    )?;
+    stream.flush()?;
+
+    let mut solutions = solver.solutions(&ignore_words);
+    let mut seen = HashSet::new();
+    while let Some(solution) = solutions.next() {
+        // only look for more with the same number of words as the first
+        solutions.set_max_words(solution.word_count());
+        // a word with a letter on two sides can be found once for each way
+        // it's played
+        if !seen.insert(solution.words.clone()) {
+            continue;
+        }
+        writeln!(stream, "{}", solution.to_json())?;
+        stream.flush()?;
+        if seen.len() >= solver.solution_limit(solution.word_count()) {
+            break;
+        }
+    }
+    Ok(())
+}
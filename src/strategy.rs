@@ -0,0 +1,246 @@
+use std::cmp::Reverse;
+use std::time::Instant;
+
+use crate::{
+    log, word_bit, Algorithm, CancelToken, Progress, ProgressCallback, SearchStats, Solution,
+    Solver,
+};
+
+// a way of searching a board for solutions. each --algo is one of these,
+// and `Solver::set_strategy` takes any other. the solver has the board,
+// the words that fit it and the options, and `run_solver` ranks what the
+// strategy finds and times it, so a strategy only has to search
+pub trait SearchStrategy: Send + Sync {
+    fn solve(&self, solver: &Solver, ignore_words: &[String]) -> SearchResult;
+}
+
+// the solutions a strategy found, with the fewest words it could find, and
+// what it took to find them
+#[derive(Debug, Clone, Default)]
+pub struct SearchResult {
+    pub solutions: Vec<Solution>,
+    pub stats: SearchStats,
+}
+
+impl Algorithm {
+    pub fn strategy(self) -> &'static dyn SearchStrategy {
+        match self {
+            Algorithm::AStar => &Queued(Algorithm::AStar),
+            Algorithm::Ida => &Queued(Algorithm::Ida),
+            Algorithm::Dfs => &Queued(Algorithm::Dfs),
+            Algorithm::Sma => &Queued(Algorithm::Sma),
+            Algorithm::Beam => &Beam,
+            Algorithm::Join => &Join,
+        }
+    }
+}
+
+// A*, IDA*, depth first and SMA* share the queue and stack in `Solutions`,
+// which goes by the solver's algorithm. chains of up to three words are
+// joined from the word indexes first, since that's quicker than any search
+struct Queued(Algorithm);
+
+impl SearchStrategy for Queued {
+    fn solve(&self, solver: &Solver, ignore_words: &[String]) -> SearchResult {
+        if solver.algorithm != self.0 {
+            let mut solver = solver.clone();
+            solver.algorithm = self.0;
+            return self.solve(&solver, ignore_words);
+        }
+        if let Some(solutions) = Join.join(solver, ignore_words) {
+            return solutions;
+        }
+        let (solutions, stats) = if solver.threads > 1 {
+            solver.run_parallel(ignore_words)
+        } else {
+            let mut solutions = solver.solutions(ignore_words);
+            (Solver::best_solutions(&mut solutions), solutions.stats())
+        };
+        SearchResult { solutions, stats }
+    }
+}
+
+// only the chains of up to three words that can be joined straight from
+// the word indexes, without searching. boards with more letters than the
+// covering words table takes, or without a chain that short, have none
+struct Join;
+
+impl Join {
+    fn join(&self, solver: &Solver, ignore_words: &[String]) -> Option<SearchResult> {
+        let solutions = solver.join_short_solutions(ignore_words)?;
+        Some(SearchResult {
+            solutions,
+            stats: SearchStats {
+                joined: true,
+                ..SearchStats::default()
+            },
+        })
+    }
+}
+
+impl SearchStrategy for Join {
+    fn solve(&self, solver: &Solver, ignore_words: &[String]) -> SearchResult {
+        self.join(solver, ignore_words).unwrap_or_default()
+    }
+}
+
+// a chain in the beam, with what it's covered so far
+struct BeamChain {
+    chain: Vec<usize>,
+    used_words: u64,
+    coverage: u64,
+    missing_required: usize,
+    heuristic: usize,
+}
+
+// breadth first a word at a time, only keeping the chains that look best
+// at each length, the fewest words still needed then the most letters
+// covered. it takes as long and as much memory as the beam is wide
+// whatever the board, but a chain it drops might have led to the best
+// solution, or the only one
+struct Beam;
+
+impl SearchStrategy for Beam {
+    fn solve(&self, solver: &Solver, ignore_words: &[String]) -> SearchResult {
+        let mut stats = SearchStats::default();
+        let words = match solver.join_words(ignore_words) {
+            Some(words) => words,
+            None => return SearchResult::default(),
+        };
+        let full_mask = solver.puzzle.full_mask();
+        let deadline = solver.deadline();
+        let mut should_stop = |nodes_expanded: usize| {
+            stats.stopped = stats.stopped
+                || solver
+                    .cancel
+                    .as_ref()
+                    .is_some_and(CancelToken::is_cancelled)
+                || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                || solver
+                    .max_nodes
+                    .is_some_and(|max_nodes| nodes_expanded >= max_nodes);
+            stats.stopped
+        };
+        let missing_required = |chain: &[usize]| {
+            words
+                .required
+                .iter()
+                .filter(|&&r| !chain.iter().any(|&i| solver.is_same_word(i, r)))
+                .count()
+        };
+
+        let mut nodes_expanded = 0;
+        let mut nodes_pruned = 0;
+        let mut beam: Vec<BeamChain> = Vec::new();
+        for i in (0..solver.words.dictionary.len()).filter(|&i| words.is_first[i]) {
+            let coverage = solver.words.word_masks[i];
+            let missing_required = missing_required(&[i]);
+            let heuristic = solver.heuristic(coverage, missing_required, i);
+            if 1 + heuristic > solver.max_words {
+                nodes_pruned += 1;
+                continue;
+            }
+            beam.push(BeamChain {
+                chain: vec![i],
+                used_words: word_bit(solver.words.word_ids[i]),
+                coverage,
+                missing_required,
+                heuristic,
+            });
+        }
+
+        let mut found = Vec::new();
+        let mut peak = 0;
+        for depth in 1..=solver.max_words {
+            self.narrow(solver, &mut beam, &mut nodes_pruned);
+            peak = peak.max(beam.len());
+            log::debug!("{} chains of {} words in the beam", beam.len(), depth);
+            if let Some(ProgressCallback(callback)) = &solver.progress {
+                callback(&Progress {
+                    nodes_expanded,
+                    depth,
+                    queue_size: beam.len(),
+                    best_coverage: beam
+                        .first()
+                        .map_or(0, |state| state.coverage.count_ones() as usize),
+                    letter_count: full_mask.count_ones() as usize,
+                });
+            }
+            found.extend(
+                beam.iter()
+                    .filter(|state| state.coverage == full_mask && state.missing_required == 0)
+                    .map(|state| solver.chain_solution(&state.chain)),
+            );
+            if !found.is_empty() || depth == solver.max_words || should_stop(nodes_expanded) {
+                break;
+            }
+
+            let mut next = Vec::new();
+            for state in &beam {
+                nodes_expanded += 1;
+                let last = state.chain[state.chain.len() - 1];
+                for &i in solver.followers(last) {
+                    let bit = word_bit(solver.words.word_ids[i]);
+                    let repeated = state.used_words & bit != 0
+                        && state.chain.iter().any(|&j| solver.is_same_word(i, j));
+                    if repeated
+                        || !words.usable[i]
+                        || !solver.allows(state.chain.iter().copied(), i)
+                    {
+                        nodes_pruned += 1;
+                        continue;
+                    }
+                    let coverage = state.coverage | solver.words.word_masks[i];
+                    // the word isn't in the chain yet, so any required
+                    // word it is was still missing
+                    let missing_required = state.missing_required.saturating_sub(
+                        words
+                            .required
+                            .iter()
+                            .filter(|&&r| solver.is_same_word(i, r))
+                            .count(),
+                    );
+                    let heuristic = solver.heuristic(coverage, missing_required, i);
+                    if depth + 1 + heuristic > solver.max_words {
+                        nodes_pruned += 1;
+                        continue;
+                    }
+                    let mut chain = state.chain.clone();
+                    chain.push(i);
+                    next.push(BeamChain {
+                        chain,
+                        used_words: state.used_words | bit,
+                        coverage,
+                        missing_required,
+                        heuristic,
+                    });
+                }
+            }
+            beam = next;
+        }
+
+        found.truncate(
+            found
+                .first()
+                .map_or(0, |solution| solver.search_limit(solution.word_count())),
+        );
+        stats.nodes_expanded = nodes_expanded;
+        stats.nodes_pruned = nodes_pruned;
+        stats.peak_queue_size = peak;
+        SearchResult {
+            solutions: found,
+            stats,
+        }
+    }
+}
+
+impl Beam {
+    // the best chains, as many as the beam is wide
+    fn narrow(&self, solver: &Solver, beam: &mut Vec<BeamChain>, nodes_pruned: &mut usize) {
+        beam.sort_by_key(|state| (state.heuristic, Reverse(state.coverage.count_ones())));
+        if beam.len() > solver.beam_width {
+            *nodes_pruned += beam.len() - solver.beam_width;
+            beam.truncate(solver.beam_width);
+        }
+    }
+}
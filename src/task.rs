@@ -0,0 +1,107 @@
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::{solve_with, CancelToken, Dictionary, LbError, Puzzle, Solution, SolveOptions, Solver};
+
+// a search running on a thread of its own, for async code: awaiting it
+// never blocks the executor, whichever runtime that is, and it wakes the
+// task that's waiting once the search is done. dropping it before then
+// cancels the search, the same as cancelling its token
+pub struct SolveTask {
+    shared: Arc<Mutex<Shared>>,
+    // none when the caller gave the solver a token of their own
+    cancel: Option<CancelToken>,
+}
+
+type Outcome = thread::Result<Result<Vec<Solution>, LbError>>;
+
+#[derive(Default)]
+struct Shared {
+    outcome: Option<Outcome>,
+    waker: Option<Waker>,
+}
+
+impl SolveTask {
+    fn spawn<F>(cancel: Option<CancelToken>, search: F) -> SolveTask
+    where
+        F: FnOnce() -> Result<Vec<Solution>, LbError> + Send + 'static,
+    {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let finished = Arc::clone(&shared);
+        thread::spawn(move || {
+            // a panic is handed on to whoever awaits the task, rather than
+            // leaving them waiting for ever
+            let outcome = panic::catch_unwind(AssertUnwindSafe(search));
+            let mut finished = finished.lock().unwrap_or_else(|err| err.into_inner());
+            finished.outcome = Some(outcome);
+            if let Some(waker) = finished.waker.take() {
+                waker.wake();
+            }
+        });
+        SolveTask { shared, cancel }
+    }
+
+    // stop the search early, it finishes with whatever it's found by then
+    pub fn cancel(&self) {
+        if let Some(cancel) = &self.cancel {
+            cancel.cancel();
+        }
+    }
+}
+
+impl Future for SolveTask {
+    type Output = Result<Vec<Solution>, LbError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap_or_else(|err| err.into_inner());
+        match shared.outcome.take() {
+            Some(Ok(found)) => Poll::Ready(found),
+            Some(Err(payload)) => panic::resume_unwind(payload),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for SolveTask {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl Solver {
+    // `run_solver` on another thread. the solver is cloned for it, which
+    // shares the word list rather than copying it
+    pub fn solve_async(&self, ignore_words: &[String]) -> SolveTask {
+        let mut solver = self.clone();
+        let cancel = match solver.cancel {
+            Some(_) => None,
+            None => {
+                let cancel = CancelToken::new();
+                solver.cancel = Some(cancel.clone());
+                Some(cancel)
+            }
+        };
+        let ignore_words = ignore_words.to_vec();
+        SolveTask::spawn(cancel, move || solver.run_solver(&ignore_words))
+    }
+}
+
+// `solve` on another thread, from a word list that's already been loaded,
+// so picking out the words that fit the board happens there too
+pub fn solve_async(dictionary: &Dictionary, board: &[String], options: &SolveOptions) -> SolveTask {
+    let (dictionary, board, options) = (dictionary.clone(), board.to_vec(), options.clone());
+    let cancel = CancelToken::new();
+    let token = cancel.clone();
+    SolveTask::spawn(Some(cancel), move || {
+        let mut solver = dictionary.solver_for(&Puzzle::new(&board)?);
+        solver.set_cancel_token(Some(token));
+        solve_with(solver, &options)
+    })
+}
@@ -0,0 +1,55 @@
+use std::process::Command;
+
+use lbsolver::json;
+
+// the page the day's board is embedded in
+const URL: &str = "https://www.nytimes.com/puzzles/letter-boxed";
+
+// the board from the NYT site, with the words it accepts if it gave them
+pub struct TodaysPuzzle {
+    pub sides: Vec<String>,
+    pub dictionary: Option<Vec<String>>,
+}
+
+// download today's board, using curl so there's no need for an HTTP client
+pub fn fetch() -> Result<TodaysPuzzle, String> {
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location", URL])
+        .output()
+        .map_err(|err| format!("could not run curl to fetch {}: {}", URL, err))?;
+    if !output.status.success() {
+        return Err(format!(
+            "could not fetch {}: {}",
+            URL,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse(&String::from_utf8_lossy(&output.stdout))
+}
+
+// the page sets `window.gameData = {...}` with the sides and dictionary
+fn parse(html: &str) -> Result<TodaysPuzzle, String> {
+    let start = html
+        .find("window.gameData")
+        .ok_or("could not find the game data on the page")?;
+    let rest = &html[start..];
+    let equals = rest
+        .find('=')
+        .ok_or("could not find the game data on the page")?;
+    let (game_data, _) =
+        json::parse_prefix(&rest[equals + 1..]).map_err(|err| format!("bad game data: {}", err))?;
+
+    let sides = game_data
+        .get("sides")
+        .and_then(json::Value::as_strings)
+        .ok_or("the game data has no sides")?
+        .into_iter()
+        .map(|side| side.to_lowercase())
+        .collect();
+    let dictionary = game_data
+        .get("dictionary")
+        .and_then(json::Value::as_strings)
+        .map(|words| words.into_iter().map(|word| word.to_lowercase()).collect());
+
+    Ok(TodaysPuzzle { sides, dictionary })
+}
@@ -0,0 +1,336 @@
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+
+use lbsolver::{Solution, Solver};
+
+use crate::Args;
+
+// the most solutions the list will hold
+const MAX_SOLUTIONS: usize = 500;
+
+const HELP: &str = "up/down or j/k move  / filter  1-9 ignore that word  u undo ignore  q quit";
+
+// puts the terminal into raw mode for as long as it's alive, using stty so
+// there's no need for a terminal library
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> io::Result<RawMode> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        // switch to the alternate screen and hide the cursor
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+        Ok(RawMode {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed, is stdin a terminal?"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// rows and columns, falling back to a classic 24x80
+fn terminal_size() -> (usize, usize) {
+    stty(&["size"])
+        .ok()
+        .and_then(|size| {
+            let mut parts = size.split_whitespace().map(|n| n.parse().ok());
+            Some((parts.next()??, parts.next()??))
+        })
+        .filter(|&(rows, columns)| rows > 2 && columns > 0)
+        .unwrap_or((24, 80))
+}
+
+enum Key {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Enter,
+    Escape,
+    Backspace,
+    Char(char),
+}
+
+fn read_key(stdin: &mut impl Read) -> Option<Key> {
+    let mut byte = [0u8; 1];
+    stdin.read_exact(&mut byte).ok()?;
+    Some(match byte[0] {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        0x1b => {
+            // arrow and page keys come as escape sequences, a lone
+            // escape is just the escape key
+            let mut sequence = [0u8; 2];
+            if stdin.read_exact(&mut sequence).is_err() || sequence[0] != b'[' {
+                return Some(Key::Escape);
+            }
+            match sequence[1] {
+                b'A' => Key::Up,
+                b'B' => Key::Down,
+                b'5' | b'6' => {
+                    // the sequence ends with a ~
+                    stdin.read_exact(&mut byte).ok()?;
+                    if sequence[1] == b'5' {
+                        Key::PageUp
+                    } else {
+                        Key::PageDown
+                    }
+                }
+                _ => Key::Escape,
+            }
+        }
+        b => Key::Char(b as char),
+    })
+}
+
+struct Tui<'a> {
+    solver: &'a Solver,
+    ignore_words: Vec<String>,
+    solutions: Vec<Solution>,
+    selected: usize,
+    offset: usize,
+    filter: String,
+    editing_filter: bool,
+}
+
+impl Tui<'_> {
+    // search again, e.g. after the ignore list has changed
+    fn solve(&mut self) {
+        let mut search = self.solver.solutions(&self.ignore_words);
+        self.solutions = match search.next() {
+            Some(first) => {
+                // keep to solutions with as few words as the best one
+                search.set_max_words(first.word_count());
+                std::iter::once(first)
+                    .chain(search.take(MAX_SOLUTIONS - 1))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        self.selected = 0;
+        self.offset = 0;
+    }
+
+    // the solutions that match the filter
+    fn visible(&self) -> Vec<&Solution> {
+        self.solutions
+            .iter()
+            .filter(|solution| {
+                self.filter.is_empty()
+                    || solution
+                        .words
+                        .iter()
+                        .any(|word| word.contains(&self.filter))
+            })
+            .collect()
+    }
+
+    fn move_selection(&mut self, by: isize) {
+        let count = self.visible().len();
+        if count == 0 {
+            return;
+        }
+        self.selected = self.selected.saturating_add_signed(by).min(count - 1);
+    }
+
+    // a board letter, bold green if the selected solution uses it
+    fn letter(&self, c: char, coverage: u64) -> String {
+        let bit = self.solver.puzzle().letter_mask(&c.to_string());
+        if bit & coverage != 0 {
+            format!("\x1b[1;32m{}\x1b[0m", c)
+        } else {
+            format!("\x1b[2m{}\x1b[0m", c)
+        }
+    }
+
+    fn board_lines(&self, coverage: u64) -> Vec<String> {
+        let sides = self.solver.puzzle().letter_groups();
+        let spaced = |side: &Vec<char>| {
+            side.iter()
+                .map(|&c| self.letter(c, coverage))
+                .collect::<Vec<_>>()
+                .join("   ")
+        };
+
+        // anything other than a square is shown a side per line
+        if sides.len() != 4 {
+            return sides
+                .iter()
+                .enumerate()
+                .map(|(i, side)| format!("  side {}: {}", i + 1, spaced(side)))
+                .collect();
+        }
+
+        let size = sides[0].len();
+        let width = size * 4 + 1;
+        let mut lines = vec![
+            format!("      {}", spaced(&sides[0])),
+            format!("   +{}+", "-".repeat(width)),
+        ];
+        for (&left, &right) in sides[3].iter().zip(&sides[1]) {
+            lines.push(format!(
+                " {} |{}| {}",
+                self.letter(left, coverage),
+                " ".repeat(width),
+                self.letter(right, coverage)
+            ));
+        }
+        lines.push(format!("   +{}+", "-".repeat(width)));
+        lines.push(format!("      {}", spaced(&sides[2])));
+        lines
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        let (rows, columns) = terminal_size();
+        let visible = self.visible();
+        let selected = visible.get(self.selected).copied();
+        let coverage = selected.map_or(0, |solution| {
+            self.solver.puzzle().chain_mask(&solution.words)
+        });
+
+        let mut lines = vec![String::new()];
+        lines.extend(self.board_lines(coverage));
+        lines.push(String::new());
+        lines.push(format!(
+            " {} solutions, {} shown   ignoring: {}",
+            self.solutions.len(),
+            visible.len(),
+            if self.ignore_words.is_empty() {
+                "nothing".to_string()
+            } else {
+                self.ignore_words.join(", ")
+            }
+        ));
+        lines.push(if self.editing_filter {
+            format!(" filter: {}_", self.filter)
+        } else if self.filter.is_empty() {
+            String::new()
+        } else {
+            format!(" filter: {}", self.filter)
+        });
+
+        // fit the list into whatever room is left, keeping the selection on screen
+        let list_rows = rows.saturating_sub(lines.len() + 1).max(1);
+        let mut offset = self.offset;
+        if self.selected < offset {
+            offset = self.selected;
+        } else if self.selected >= offset + list_rows {
+            offset = self.selected + 1 - list_rows;
+        }
+        for (i, solution) in visible.iter().enumerate().skip(offset).take(list_rows) {
+            let text = format!(
+                " {:>3}. {} ({} letters)",
+                i + 1,
+                solution.words.join(" - "),
+                solution.letter_count
+            );
+            let text: String = text.chars().take(columns).collect();
+            if i == self.selected {
+                lines.push(format!("\x1b[7m{}\x1b[0m", text));
+            } else {
+                lines.push(text);
+            }
+        }
+        while lines.len() < rows - 1 {
+            lines.push(String::new());
+        }
+        lines.truncate(rows - 1);
+        lines.push(format!("\x1b[2m {}\x1b[0m", HELP));
+        self.offset = offset;
+
+        let mut out = io::stdout().lock();
+        write!(out, "\x1b[2J\x1b[H{}", lines.join("\r\n"))?;
+        out.flush()
+    }
+
+    // returns false when it's time to quit
+    fn handle(&mut self, key: Key) -> bool {
+        if self.editing_filter {
+            match key {
+                Key::Enter | Key::Escape => self.editing_filter = false,
+                Key::Backspace => {
+                    self.filter.pop();
+                }
+                Key::Char(c) if !c.is_control() => self.filter.push(c),
+                _ => {}
+            }
+            self.selected = 0;
+            return true;
+        }
+
+        match key {
+            Key::Char('q') => return false,
+            Key::Up | Key::Char('k') => self.move_selection(-1),
+            Key::Down | Key::Char('j') => self.move_selection(1),
+            Key::PageUp => self.move_selection(-10),
+            Key::PageDown => self.move_selection(10),
+            Key::Char('/') => {
+                self.editing_filter = true;
+                self.filter.clear();
+            }
+            Key::Escape => self.filter.clear(),
+            Key::Char(c @ '1'..='9') => {
+                let n = c as usize - '1' as usize;
+                let word = self
+                    .visible()
+                    .get(self.selected)
+                    .and_then(|solution| solution.words.get(n).cloned());
+                if let Some(word) = word {
+                    self.ignore_words.push(word);
+                    self.solve();
+                }
+            }
+            Key::Char('u') if self.ignore_words.pop().is_some() => self.solve(),
+            _ => {}
+        }
+        true
+    }
+}
+
+// a full screen view of the board and its solutions
+pub fn run(args: &Args, solver: &Solver) -> io::Result<()> {
+    let _raw_mode = RawMode::enable()?;
+
+    let mut tui = Tui {
+        solver,
+        ignore_words: args.ignore_words.clone(),
+        solutions: Vec::new(),
+        selected: 0,
+        offset: 0,
+        filter: String::new(),
+        editing_filter: false,
+    };
+    tui.solve();
+
+    let mut stdin = io::stdin().lock();
+    loop {
+        tui.draw()?;
+        let key = match read_key(&mut stdin) {
+            Some(key) => key,
+            None => return Ok(()),
+        };
+        if !tui.handle(key) {
+            return Ok(());
+        }
+    }
+}
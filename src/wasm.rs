@@ -0,0 +1,88 @@
+// raw exports for running the solver in a browser, built with
+//
+//   cargo build --release --lib --target wasm32-unknown-unknown
+//
+// and wrapped up as `solve(board, options)` by wasm/lbsolver.js
+//
+// strings go in as utf-8 in the module's memory: the caller makes room
+// with lb_alloc, copies the bytes in, passes the pointer and length, then
+// frees it with lb_free. results come back as JSON in a buffer owned by
+// the module, which lb_result_len gives the length of and which is kept
+// until the next call
+
+use std::slice;
+use std::sync::Mutex;
+
+use crate::{json, Solution, SolveOptions};
+
+// the word list, loaded once and used for every board
+static WORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+// the last result handed back to the caller
+static RESULT: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+#[no_mangle]
+pub extern "C" fn lb_alloc(len: usize) -> *mut u8 {
+    let mut buffer = Vec::<u8>::with_capacity(len);
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+    ptr
+}
+
+/// # Safety
+///
+/// `ptr` and `len` must be from a single call to `lb_alloc`, and it can
+/// only be freed once
+#[no_mangle]
+pub unsafe extern "C" fn lb_free(ptr: *mut u8, len: usize) {
+    drop(Vec::from_raw_parts(ptr, 0, len));
+}
+
+/// load the word list, one word per line, returning how many words it has
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn lb_load_dictionary(ptr: *const u8, len: usize) -> usize {
+    let words = crate::read_words(slice::from_raw_parts(ptr, len)).unwrap_or_default();
+    let count = words.len();
+    *WORDS.lock().unwrap() = words;
+    count
+}
+
+/// solve a board, the request is a JSON object like {"board": ["abc",
+/// "def", "ghi", "jkl"], "ignore": ["word"], "max_words": 3} and the result
+/// is {"solutions": [...]} or {"error": "..."}
+///
+/// # Safety
+///
+/// `ptr` must point to `len` readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn lb_solve(ptr: *const u8, len: usize) -> *const u8 {
+    let request = String::from_utf8_lossy(slice::from_raw_parts(ptr, len));
+    let response = match solve_request(&request) {
+        Ok(response) => response,
+        Err(err) => json::Value::object().with("error", err),
+    };
+    let mut result = RESULT.lock().unwrap();
+    *result = response.to_string().into_bytes();
+    result.as_ptr()
+}
+
+#[no_mangle]
+pub extern "C" fn lb_result_len() -> usize {
+    RESULT.lock().unwrap().len()
+}
+
+fn solve_request(request: &str) -> Result<json::Value, String> {
+    let request = json::parse(request).map_err(|err| err.to_string())?;
+    let board = request
+        .get("board")
+        .and_then(json::Value::as_strings)
+        .ok_or("the request needs a board, a list of sides")?;
+    let options = SolveOptions::from_json(&request);
+    let words = WORDS.lock().unwrap();
+    let solutions = crate::solve(&board, &words, &options).map_err(|err| err.to_string())?;
+    let solutions: Vec<json::Value> = solutions.iter().map(Solution::to_json).collect();
+    Ok(json::Value::object().with("solutions", solutions))
+}
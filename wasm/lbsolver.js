@@ -0,0 +1,57 @@
+// loads the solver built for wasm32-unknown-unknown and wraps its raw
+// exports (see src/wasm.rs) up as solve(board, options)
+//
+//   const words = await (await fetch("word-list.txt")).text();
+//   const solver = await load("lbsolver.wasm", words);
+//   solver.solve(["rme", "wca", "ots", "ihn"], { ignore: ["word"], max_words: 3 });
+//
+// the options are the same as the command line's: ignore, require,
+// start_with, max_words, min_word_length and pairs
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+// `wasm` is the module's URL, or its bytes when there's no fetch (e.g. node)
+export async function load(wasm, dictionary) {
+  const bytes =
+    typeof wasm === "string" ? await (await fetch(wasm)).arrayBuffer() : wasm;
+  const { instance } = await WebAssembly.instantiate(bytes);
+  const exports = instance.exports;
+
+  // copy a string into the module's memory for the length of a call
+  function call(fn, text) {
+    const input = encoder.encode(text);
+    const ptr = exports.lb_alloc(input.length);
+    new Uint8Array(exports.memory.buffer, ptr, input.length).set(input);
+    try {
+      return fn(ptr, input.length);
+    } finally {
+      exports.lb_free(ptr, input.length);
+    }
+  }
+
+  const wordCount = call(exports.lb_load_dictionary, dictionary);
+
+  return {
+    wordCount,
+
+    // the best solutions for the board, as {words, word_count, letter_count}
+    solve(board, options = {}) {
+      const request = JSON.stringify({
+        ...options,
+        board: board.map((side) => side.toLowerCase()),
+      });
+      const ptr = call(exports.lb_solve, request);
+      const result = new Uint8Array(
+        exports.memory.buffer,
+        ptr,
+        exports.lb_result_len(),
+      );
+      const response = JSON.parse(decoder.decode(result));
+      if (response.error) {
+        throw new Error(response.error);
+      }
+      return response.solutions;
+    },
+  };
+}